@@ -0,0 +1,66 @@
+//! Compares the ways of pulling a large number of entries that expire together
+//! out of a `DelayQueue`.
+//!
+//! Run with `cargo bench --features tokio --bench delay_queue`.
+
+use std::time::{Duration, Instant};
+
+use futures::future::poll_fn;
+use timer_kit::DelayQueue;
+
+const ENTRIES: usize = 100_000;
+
+type Queue = DelayQueue<tokio::time::Sleep, usize>;
+
+async fn expired_queue() -> Queue {
+    let mut queue = Queue::with_capacity(ENTRIES);
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(10);
+    for i in 0..ENTRIES {
+        queue.insert_at(i, deadline);
+    }
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    queue
+}
+
+async fn poll_expired(mut queue: Queue) -> usize {
+    let mut count = 0;
+    while poll_fn(|cx| queue.poll_expired(cx)).await.is_some() {
+        count += 1;
+    }
+    count
+}
+
+async fn poll_expired_batch(mut queue: Queue) -> usize {
+    let mut buf = Vec::with_capacity(1024);
+    let mut count = 0;
+    loop {
+        let n = poll_fn(|cx| queue.poll_expired_batch(cx, &mut buf, 1024)).await;
+        if n == 0 {
+            break count;
+        }
+        count += n;
+        buf.clear();
+    }
+}
+
+async fn drain_expired(mut queue: Queue) -> usize {
+    queue.drain_expired().len()
+}
+
+macro_rules! bench {
+    ($name:ident) => {{
+        let queue = expired_queue().await;
+        let start = Instant::now();
+        let count = $name(queue).await;
+        let elapsed = start.elapsed();
+        assert_eq!(count, ENTRIES);
+        println!("{:<20} {:>10.2?} ({} entries)", stringify!($name), elapsed, count);
+    }};
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    bench!(poll_expired);
+    bench!(poll_expired_batch);
+    bench!(drain_expired);
+}
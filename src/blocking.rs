@@ -0,0 +1,234 @@
+//! Blocking counterparts of the timer tools for synchronous code.
+//!
+//! The functions and methods in this module block the current thread instead of returning a
+//! future. They drive the same [`Sleep`], [`Interval`] and [`DelayQueue`] as the async API,
+//! parking the thread until the underlying [`Delay`] wakes it up, so deadlines, the
+//! [`MissedTickBehavior`](crate::MissedTickBehavior) of an interval and the expiration order of a
+//! queue behave exactly the same.
+//!
+//! The [`Delay`] implementation must be usable outside of its async runtime, which is the case
+//! for `smol::Timer` and `futures_timer::Delay`. A `tokio::time::Sleep` needs the thread to have
+//! entered, with `tokio::runtime::Handle::enter`, a multi-threaded `tokio` runtime that keeps
+//! driving its timer while the thread is blocked.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use timer_kit::{blocking, Interval};
+//!
+//! # #[cfg(feature = "smol")]
+//! # fn example() {
+//! blocking::sleep::<smol::Timer>(Duration::from_millis(100));
+//!
+//! let mut interval = Interval::<smol::Timer>::new(Duration::from_millis(100));
+//! for _ in interval.iter_blocking().take(3) {
+//!     // ...
+//! }
+//!
+//! let result = blocking::timeout::<smol::Timer, _, _>(Duration::from_secs(1), || 1 + 1);
+//! assert_eq!(result.unwrap(), 2);
+//! # }
+//! ```
+
+use std::{
+    fmt,
+    future::{poll_fn, Future},
+    pin::pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+    time::Duration,
+};
+
+use crate::{error::Elapsed, Delay, DelayQueue, Expired, Interval, Sleep, Timeout};
+
+/// Blocks the current thread for `duration`.
+pub fn sleep<D>(duration: Duration)
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    block_on(Sleep::<D>::new(duration));
+}
+
+/// Blocks the current thread until `deadline` is reached.
+pub fn sleep_until<D>(deadline: D::Instant)
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    block_on(Sleep::<D>::new_until(deadline));
+}
+
+/// Runs `f` on a helper thread and waits for at most `duration` for its result.
+///
+/// # Errors
+///
+/// Returns [`Elapsed`] if `f` did not return within `duration`. A thread cannot be cancelled, so
+/// `f` keeps running to completion on the helper thread and its result is dropped.
+///
+/// # Panics
+///
+/// Panics if the helper thread cannot be spawned, or if `f` panics.
+pub fn timeout<D, F, R>(duration: Duration, f: F) -> Result<R, Elapsed>
+where
+    D: Delay,
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let slot = Arc::new(Mutex::new(Slot {
+        result: None,
+        waker: None,
+        closed: false,
+    }));
+
+    let sender = Sender(slot.clone());
+    thread::Builder::new()
+        .name("timer-kit-blocking-timeout".into())
+        .spawn(move || sender.send(f()))
+        .expect("failed to spawn the helper thread");
+
+    block_on(Timeout::<D, _>::new(duration, Receiver(slot)))
+}
+
+/// An iterator over the ticks of an [`Interval`] that blocks the current thread until the next
+/// tick.
+///
+/// This is created by [`Interval::iter_blocking`], and never returns `None`.
+pub struct Ticks<'a, D: Delay> {
+    interval: &'a mut Interval<D>,
+}
+
+impl<D> fmt::Debug for Ticks<'_, D>
+where
+    D: Delay + fmt::Debug,
+    D::Instant: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ticks")
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+
+impl<D> Iterator for Ticks<'_, D>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    type Item = D::Value;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.interval.tick_blocking())
+    }
+}
+
+impl<D> Interval<D>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    /// Blocks the current thread until the next tick of the [`Interval`].
+    ///
+    /// This is the blocking counterpart of [`tick`](Self::tick).
+    pub fn tick_blocking(&mut self) -> D::Value {
+        block_on(poll_fn(|cx| self.poll_tick(cx)))
+    }
+
+    /// Returns an iterator that blocks the current thread until every tick of the [`Interval`].
+    pub fn iter_blocking(&mut self) -> Ticks<'_, D> {
+        Ticks { interval: self }
+    }
+}
+
+impl<D, T> DelayQueue<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    /// Blocks the current thread until the next value of the queue expires, for at most
+    /// `timeout`.
+    ///
+    /// This is the blocking counterpart of [`poll_expired`](Self::poll_expired). Returns
+    /// `Ok(None)` if the queue is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Elapsed`] if no value expired within `timeout`.
+    pub fn next_expired_blocking(&mut self, timeout: Duration) -> Result<Option<Expired<T, D::Instant>>, Elapsed> {
+        block_on(Timeout::<D, _>::new(timeout, poll_fn(|cx| self.poll_expired(cx))))
+    }
+}
+
+/// Polls `fut` on the current thread, parking the thread until it is woken up.
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = pin!(fut);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+struct Slot<R> {
+    result: Option<R>,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+/// Sends the result of the helper thread of [`timeout`] to the waiting thread
+struct Sender<R>(Arc<Mutex<Slot<R>>>);
+
+/// Receives the result of the helper thread of [`timeout`]
+struct Receiver<R>(Arc<Mutex<Slot<R>>>);
+
+impl<R> Sender<R> {
+    fn send(self, result: R) {
+        self.0.lock().unwrap().result = Some(result);
+    }
+}
+
+impl<R> Drop for Sender<R> {
+    fn drop(&mut self) {
+        // Also runs when the closure panics, so that the waiting thread does not wait for the
+        // whole timeout.
+        let mut slot = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        slot.closed = true;
+        if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<R> Future for Receiver<R> {
+    type Output = R;
+
+    fn poll(self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<R> {
+        let mut slot = self.0.lock().unwrap();
+        match slot.result.take() {
+            Some(result) => Poll::Ready(result),
+            None if slot.closed => panic!("the closure passed to `blocking::timeout` panicked"),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
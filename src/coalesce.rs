@@ -0,0 +1,370 @@
+//! Coalescing of timers whose deadlines are allowed to slip.
+//!
+//! See [`Coalescer`] for more details.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll, Wake, Waker},
+    time::Duration,
+};
+
+use crate::{util::saturating_add, Delay, DelayQueue, DelayQueueBuilder, Instant, Interval, Sleep};
+
+/// Groups timers whose slack windows overlap onto a single underlying [`Delay`], so that they
+/// fire together and the process wakes up less often.
+///
+/// Every timer created through a `Coalescer` has a window that starts at its deadline and lasts
+/// for its slack. When the timer is polled, it joins the earliest group whose firing instant lies
+/// within its window, or otherwise starts a new group that fires at the end of its window. Each
+/// group arms a single delay of type `D`, and all of its timers complete when that delay fires.
+///
+/// [`armed_delays`] counts the delays armed by the coalescer, and [`armed_timers`] counts the
+/// timers that were armed through it, so that the savings can be measured.
+///
+/// The slack of every timer defaults to the slack given to [`Coalescer::new`], and can be
+/// changed with [`Sleep::set_slack`], [`Interval::set_slack`] or [`DelayQueue::set_slack`].
+///
+/// A `Coalescer` is a cheap handle that can be cloned and shared across tasks and threads.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use timer_kit::Coalescer;
+///
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
+/// let coalescer = Coalescer::<smol::Timer>::new(Duration::from_millis(50));
+///
+/// let first = coalescer.sleep(Duration::from_millis(100));
+/// let second = coalescer.sleep(Duration::from_millis(120));
+/// futures::join!(first, second);
+///
+/// assert_eq!(coalescer.armed_timers(), 2);
+/// assert_eq!(coalescer.armed_delays(), 1);
+/// # }
+/// ```
+///
+/// [`armed_delays`]: method@Self::armed_delays
+/// [`armed_timers`]: method@Self::armed_timers
+pub struct Coalescer<D: Delay> {
+    shared: Arc<Mutex<State<D>>>,
+    slack: Duration,
+}
+
+struct State<D: Delay> {
+    /// Groups of timers indexed by the instant at which they fire
+    groups: BTreeMap<D::Instant, Group<D>>,
+
+    /// Identifier of the next registered timer
+    next_id: u64,
+
+    armed_timers: u64,
+    armed_delays: u64,
+
+    /// Stores the value of a fired delay so that it can be cloned for every timer of its group
+    store_value: fn(D::Value) -> Value<D>,
+}
+
+/// The value of a fired delay, which does not make the coalescer `!Send` if the value is not
+type Value<D> = Box<dyn Fn() -> <D as Delay>::Value + Send + Sync>;
+
+struct Group<D: Delay> {
+    delay: Pin<Box<D>>,
+
+    /// Value of the delay once it has fired
+    value: Option<Value<D>>,
+
+    /// Number of timers in the group
+    members: usize,
+
+    wakers: Arc<FanOut>,
+}
+
+/// Wakes every timer of a group when the delay of the group fires
+#[derive(Default)]
+struct FanOut {
+    wakers: Mutex<BTreeMap<u64, Waker>>,
+}
+
+/// The registration of a [`Sleep`] with a [`Coalescer`].
+pub(crate) struct Entry<D: Delay> {
+    coalescer: Coalescer<D>,
+    id: u64,
+
+    /// Firing instant of the group the timer has joined
+    group: Option<D::Instant>,
+}
+
+fn lock<D: Delay>(shared: &Mutex<State<D>>) -> MutexGuard<'_, State<D>> {
+    // The state is consistent even if a timer panicked while holding the lock
+    shared.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+impl<D> Coalescer<D>
+where
+    D: Delay,
+{
+    /// Creates a new `Coalescer` whose timers have a slack of `slack` by default.
+    pub fn new(slack: Duration) -> Self
+    where
+        D::Value: Clone + Send + Sync + 'static,
+    {
+        let state = State {
+            groups: BTreeMap::new(),
+            next_id: 0,
+            armed_timers: 0,
+            armed_delays: 0,
+            store_value: |value: D::Value| Box::new(move || value.clone()),
+        };
+
+        Self {
+            shared: Arc::new(Mutex::new(state)),
+            slack,
+        }
+    }
+
+    /// Returns the default slack of the timers created by the coalescer.
+    pub fn slack(&self) -> Duration {
+        self.slack
+    }
+
+    /// Creates a [`Sleep`] that completes within the slack after `duration`.
+    pub fn sleep(&self, duration: Duration) -> Sleep<D> {
+        self.sleep_until(saturating_add(D::Instant::now(), duration))
+    }
+
+    /// Creates a [`Sleep`] that completes within the slack after `deadline`.
+    pub fn sleep_until(&self, deadline: D::Instant) -> Sleep<D> {
+        Sleep::coalesced(self.entry(), deadline, self.slack)
+    }
+
+    /// Creates an [`Interval`] that yields with interval of `period`, starting after one period.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `period` is zero.
+    pub fn interval(&self, period: Duration) -> Interval<D>
+    where
+        D::Instant: Unpin,
+    {
+        self.interval_at(saturating_add(D::Instant::now(), period), period)
+    }
+
+    /// Creates an [`Interval`] that yields with interval of `period`, starting at `start`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `period` is zero.
+    pub fn interval_at(&self, start: D::Instant, period: Duration) -> Interval<D>
+    where
+        D::Instant: Unpin,
+    {
+        Interval::from_sleep(self.sleep_until(start), period)
+    }
+
+    /// Creates an empty [`DelayQueue`] whose timer is coalesced.
+    pub fn delay_queue<T>(&self) -> DelayQueue<D, T>
+    where
+        D::Instant: Unpin,
+    {
+        DelayQueueBuilder::new().coalescer(self).build()
+    }
+
+    /// Returns the number of timers that were armed through the coalescer, counting every time a
+    /// timer joins a group.
+    pub fn armed_timers(&self) -> u64 {
+        lock(&self.shared).armed_timers
+    }
+
+    /// Returns the number of underlying delays that were armed by the coalescer, which is one per
+    /// group of timers.
+    pub fn armed_delays(&self) -> u64 {
+        lock(&self.shared).armed_delays
+    }
+
+    pub(crate) fn entry(&self) -> Entry<D> {
+        let mut state = lock(&self.shared);
+        let id = state.next_id;
+        state.next_id += 1;
+
+        Entry {
+            coalescer: self.clone(),
+            id,
+            group: None,
+        }
+    }
+}
+
+impl<D: Delay> State<D> {
+    /// Adds a timer to the earliest group firing within `[deadline, end]`, or to a new group
+    /// firing at `end`, returning the firing instant of the group.
+    fn join(&mut self, deadline: D::Instant, end: D::Instant) -> D::Instant {
+        self.armed_timers += 1;
+
+        let at = match self.groups.range(deadline..=end).next() {
+            Some((&at, _)) => at,
+            None => {
+                self.armed_delays += 1;
+                self.groups.insert(end, Group {
+                    delay: Box::pin(D::delay_until(end)),
+                    value: None,
+                    members: 0,
+                    wakers: Arc::default(),
+                });
+                end
+            }
+        };
+
+        if let Some(group) = self.groups.get_mut(&at) {
+            group.members += 1;
+        }
+        at
+    }
+
+    fn leave(&mut self, at: D::Instant, id: u64) {
+        if let Some(group) = self.groups.get_mut(&at) {
+            group.wakers.remove(id);
+            group.members -= 1;
+            if group.members == 0 {
+                self.groups.remove(&at);
+            }
+        }
+    }
+}
+
+impl<D: Delay> Entry<D> {
+    /// Polls the group of the timer, joining a group first if the timer is not in a group whose
+    /// firing instant lies within `[deadline, deadline + slack]`.
+    pub(crate) fn poll(&mut self, deadline: D::Instant, slack: Duration, cx: &mut Context<'_>) -> Poll<D::Value> {
+        let end = saturating_add(deadline, slack);
+        let mut state = lock(&self.coalescer.shared);
+
+        let at = match self.group {
+            Some(at) if deadline <= at && at <= end => at,
+            Some(at) => {
+                state.leave(at, self.id);
+                state.join(deadline, end)
+            }
+            None => state.join(deadline, end),
+        };
+        self.group = Some(at);
+
+        let store_value = state.store_value;
+        let group = state
+            .groups
+            .get_mut(&at)
+            .expect("a timer is always in an existing group");
+
+        let value = match group.value {
+            Some(ref value) => value(),
+            None => {
+                group.wakers.register(self.id, cx.waker());
+
+                let waker = Waker::from(group.wakers.clone());
+                match group.delay.as_mut().poll_elapsed(&mut Context::from_waker(&waker)) {
+                    Poll::Ready(value) => {
+                        let value = store_value(value);
+                        let output = value();
+                        group.value = Some(value);
+                        group.wakers.wake_all();
+                        output
+                    }
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+        };
+
+        state.leave(at, self.id);
+        self.group = None;
+        Poll::Ready(value)
+    }
+
+    /// Leaves the group of the timer unless it fires within `[deadline, deadline + slack]`.
+    pub(crate) fn reset(&mut self, deadline: D::Instant, slack: Duration) {
+        if let Some(at) = self.group {
+            if at < deadline || at > saturating_add(deadline, slack) {
+                lock(&self.coalescer.shared).leave(at, self.id);
+                self.group = None;
+            }
+        }
+    }
+}
+
+impl<D: Delay> Drop for Entry<D> {
+    fn drop(&mut self) {
+        if let Some(at) = self.group.take() {
+            lock(&self.coalescer.shared).leave(at, self.id);
+        }
+    }
+}
+
+impl FanOut {
+    fn register(&self, id: u64, waker: &Waker) {
+        let mut wakers = self.wakers.lock().unwrap_or_else(|err| err.into_inner());
+        match wakers.get_mut(&id) {
+            Some(current) if current.will_wake(waker) => {}
+            Some(current) => current.clone_from(waker),
+            None => {
+                wakers.insert(id, waker.clone());
+            }
+        }
+    }
+
+    fn remove(&self, id: u64) {
+        self.wakers
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .remove(&id);
+    }
+
+    fn wake_all(&self) {
+        let wakers = core::mem::take(&mut *self.wakers.lock().unwrap_or_else(|err| err.into_inner()));
+        for waker in wakers.into_values() {
+            waker.wake();
+        }
+    }
+}
+
+impl Wake for FanOut {
+    fn wake(self: Arc<Self>) {
+        self.wake_all();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.wake_all();
+    }
+}
+
+impl<D: Delay> Clone for Coalescer<D> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            slack: self.slack,
+        }
+    }
+}
+
+impl<D: Delay> fmt::Debug for Coalescer<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = lock(&self.shared);
+        f.debug_struct("Coalescer")
+            .field("slack", &self.slack)
+            .field("groups", &state.groups.len())
+            .field("armed_timers", &state.armed_timers)
+            .field("armed_delays", &state.armed_delays)
+            .finish()
+    }
+}
+
+impl<D: Delay> fmt::Debug for Entry<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Entry")
+            .field("id", &self.id)
+            .field("grouped", &self.group.is_some())
+            .finish()
+    }
+}
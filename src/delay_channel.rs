@@ -0,0 +1,236 @@
+//! A channel that delivers each message once its delay has elapsed.
+//!
+//! See [`delay_channel`] for more details.
+
+use std::fmt;
+use std::pin::Pin;
+use std::task::{self, Poll};
+
+use crate::error::DelaySendError;
+use crate::shared_delay_queue::Remover;
+use crate::util::saturating_add;
+use crate::{Delay, Duration, Inserter, Instant, Key, SharedDelayQueue};
+
+/// Creates a channel whose messages are received once their delay has elapsed.
+///
+/// The [`DelaySender`] can be cloned and used from any task or thread. Each
+/// sent message returns a [`DelayHandle`] that cancels the message if it has
+/// not been received yet. The [`DelayReceiver`] yields the messages in the
+/// order of their deadlines, and ends once it is empty and all the senders
+/// have been dropped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use timer_kit::delay_channel;
+///
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
+/// let (sender, mut receiver) = delay_channel::<smol::Timer, _>();
+///
+/// sender.send_after("second", Duration::from_millis(200)).unwrap();
+/// sender.send_after("first", Duration::from_millis(100)).unwrap();
+/// let handle = sender.send_after("never", Duration::from_millis(150)).unwrap();
+///
+/// // Cancelling a message gives it back
+/// assert_eq!(handle.cancel(), Some("never"));
+/// drop(sender);
+///
+/// assert_eq!(receiver.recv().await, Some("first"));
+/// assert_eq!(receiver.recv().await, Some("second"));
+/// assert_eq!(receiver.recv().await, None);
+/// # }
+/// ```
+pub fn delay_channel<D, T>() -> (DelaySender<D, T>, DelayReceiver<D, T>)
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let queue = SharedDelayQueue::new();
+    let sender = DelaySender {
+        inserter: queue.inserter(),
+    };
+    let receiver = DelayReceiver { queue };
+    (sender, receiver)
+}
+
+/// The sending half of a [`delay_channel`].
+///
+/// Senders can be cloned to send messages from several tasks or threads.
+pub struct DelaySender<D: Delay, T> {
+    inserter: Inserter<D, T>,
+}
+
+/// The receiving half of a [`delay_channel`].
+///
+/// Messages are yielded by [`recv`], [`poll_recv`] and the [`Stream`]
+/// implementation once their deadline has been reached.
+///
+/// [`recv`]: method@Self::recv
+/// [`poll_recv`]: method@Self::poll_recv
+/// [`Stream`]: futures_util::Stream
+pub struct DelayReceiver<D: Delay, T> {
+    queue: SharedDelayQueue<D, T>,
+}
+
+/// A handle to a message sent through a [`DelaySender`].
+///
+/// Dropping the handle does **not** cancel the message.
+pub struct DelayHandle<D: Delay, T> {
+    remover: Remover<D, T>,
+    key: Key,
+}
+
+impl<D, T> DelaySender<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    /// Sends `message` to be received after `delay` elapses.
+    ///
+    /// # Errors
+    ///
+    /// Returns the message along with [`DelayQueueError::Closed`] if the
+    /// receiver has been dropped, or any of the errors of
+    /// [`DelayQueue::try_insert`].
+    ///
+    /// [`DelayQueueError::Closed`]: crate::error::DelayQueueError::Closed
+    /// [`DelayQueue::try_insert`]: crate::DelayQueue::try_insert
+    pub fn send_after(&self, message: T, delay: Duration) -> Result<DelayHandle<D, T>, DelaySendError<T>> {
+        self.send_at(message, saturating_add(D::Instant::now(), delay))
+    }
+
+    /// Sends `message` to be received at `when`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the message along with [`DelayQueueError::Closed`] if the
+    /// receiver has been dropped, or any of the errors of
+    /// [`DelayQueue::try_insert_at`].
+    ///
+    /// [`DelayQueueError::Closed`]: crate::error::DelayQueueError::Closed
+    /// [`DelayQueue::try_insert_at`]: crate::DelayQueue::try_insert_at
+    pub fn send_at(&self, message: T, when: D::Instant) -> Result<DelayHandle<D, T>, DelaySendError<T>> {
+        match self.inserter.insert_or_return(message, when) {
+            Ok(key) => Ok(DelayHandle {
+                remover: self.inserter.remover(),
+                key,
+            }),
+            Err((message, error)) => Err(DelaySendError::new(message, error)),
+        }
+    }
+
+    /// Returns `true` if the receiver has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.inserter.is_closed()
+    }
+}
+
+impl<D, T> DelayReceiver<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    /// Receives the next message once its deadline has been reached.
+    ///
+    /// Returns `None` once there are no pending messages and all the senders
+    /// have been dropped.
+    pub async fn recv(&mut self) -> Option<T> {
+        futures_util::future::poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Polls for the next message whose deadline has been reached,
+    /// registering the current task for wakeup if none is available yet.
+    ///
+    /// Returns `Poll::Ready(None)` once there are no pending messages and all
+    /// the senders have been dropped.
+    pub fn poll_recv(&mut self, cx: &mut task::Context<'_>) -> Poll<Option<T>> {
+        self.queue
+            .poll_expired(cx)
+            .map(|expired| expired.map(|expired| expired.into_inner()))
+    }
+
+    /// Returns the number of messages that have not been received yet.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns `true` if there are no messages waiting to be received.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+impl<D, T> DelayHandle<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    /// Cancels the message, returning it if it had not been received yet.
+    ///
+    /// A message that was pending when the receiver was dropped is still
+    /// returned as long as a [`DelaySender`] of the channel is alive.
+    pub fn cancel(self) -> Option<T> {
+        self.remover
+            .remove(&self.key)
+            .map(|expired| expired.into_inner())
+    }
+}
+
+impl<D, T> Clone for DelaySender<D, T>
+where
+    D: Delay,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inserter: self.inserter.clone(),
+        }
+    }
+}
+
+impl<D, T> fmt::Debug for DelaySender<D, T>
+where
+    D: Delay,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DelaySender")
+            .field("inserter", &self.inserter)
+            .finish()
+    }
+}
+
+impl<D, T> fmt::Debug for DelayReceiver<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DelayReceiver")
+            .field("queue", &self.queue)
+            .finish()
+    }
+}
+
+impl<D, T> fmt::Debug for DelayHandle<D, T>
+where
+    D: Delay,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DelayHandle")
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+impl<D, T> futures_util::Stream for DelayReceiver<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        DelayReceiver::poll_recv(self.get_mut(), cx)
+    }
+}
@@ -0,0 +1,301 @@
+//! A map of delayed entries indexed by user-provided keys.
+//!
+//! See [`DelayMap`] for more details.
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::pin::Pin;
+use std::task::{self, Poll};
+
+use crate::util::saturating_add;
+use crate::{Delay, DelayQueue, Duration, Instant, Key};
+
+/// A map of delayed entries indexed by keys of type `K`.
+///
+/// A `DelayMap` behaves like a [`DelayQueue`] whose entries are looked up by
+/// the caller's own identifiers instead of the [`Key`]s handed out by the
+/// queue. Each key has at most one entry: inserting an entry for a key that
+/// is already in the map replaces the value and deadline of the existing
+/// entry.
+///
+/// Entries are yielded as `(K, V)` pairs by [`poll_expired`] and the
+/// [`Stream`] implementation once their deadline has been reached.
+///
+/// # Example
+///
+/// Expiring sessions after 30 seconds of inactivity
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use futures::StreamExt;
+/// use timer_kit::DelayMap;
+///
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
+/// let mut sessions = DelayMap::<smol::Timer, u64, String>::new();
+/// sessions.insert(1, "alice".to_string(), Duration::from_secs(30));
+/// sessions.insert(2, "bob".to_string(), Duration::from_secs(30));
+///
+/// // Activity on session 1 extends its lifetime
+/// sessions.reset(&1, Duration::from_secs(30));
+///
+/// while let Some((id, name)) = sessions.next().await {
+///     println!("session {} of {} expired", id, name);
+/// }
+/// # }
+/// ```
+///
+/// [`poll_expired`]: method@Self::poll_expired
+/// [`Stream`]: futures_util::Stream
+pub struct DelayMap<D: Delay, K, V> {
+    queue: DelayQueue<D, (K, V)>,
+    keys: HashMap<K, Key>,
+}
+
+impl<D, K, V> DelayMap<D, K, V>
+where
+    D: Delay,
+    D::Instant: Unpin,
+    K: Hash + Eq + Clone,
+{
+    /// Creates a new, empty, `DelayMap`.
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates a new, empty, `DelayMap` with the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from_queue(DelayQueue::with_capacity(capacity))
+    }
+
+    /// Creates a new, empty, `DelayMap` that stores its entries in `queue`.
+    ///
+    /// This allows configuring the underlying queue with a
+    /// [`DelayQueueBuilder`](crate::DelayQueueBuilder).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `queue` is not empty.
+    #[track_caller]
+    pub fn from_queue(queue: DelayQueue<D, (K, V)>) -> Self {
+        assert!(queue.is_empty(), "the queue must be empty");
+
+        Self {
+            queue,
+            keys: HashMap::new(),
+        }
+    }
+
+    /// Inserts `value` for `key`, set to expire after `timeout`.
+    ///
+    /// If the map already has an entry for `key`, its value is replaced and
+    /// its deadline is reset. The old value is returned.
+    ///
+    /// # Panics
+    ///
+    /// This function panics under the same conditions as [`DelayQueue::insert`].
+    #[track_caller]
+    pub fn insert(&mut self, key: K, value: V, timeout: Duration) -> Option<V> {
+        self.insert_at(key, value, saturating_add(D::Instant::now(), timeout))
+    }
+
+    /// Inserts `value` for `key`, set to expire at `when`.
+    ///
+    /// If the map already has an entry for `key`, its value is replaced and
+    /// its deadline is reset. The old value is returned.
+    ///
+    /// # Panics
+    ///
+    /// This function panics under the same conditions as [`DelayQueue::insert_at`].
+    #[track_caller]
+    pub fn insert_at(&mut self, key: K, value: V, when: D::Instant) -> Option<V> {
+        match self.keys.get(&key) {
+            Some(queue_key) => {
+                let queue_key = *queue_key;
+                self.queue.reset_at(&queue_key, when);
+                let entry = self
+                    .queue
+                    .get_mut(&queue_key)
+                    .expect("entry is in the queue");
+                Some(std::mem::replace(&mut entry.1, value))
+            }
+            None => {
+                let queue_key = self.queue.insert_at((key.clone(), value), when);
+                self.keys.insert(key, queue_key);
+                None
+            }
+        }
+    }
+
+    /// Removes the entry for `key`, returning its value if it was in the map.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let queue_key = self.keys.remove(key)?;
+        let (_, value) = self.queue.remove(&queue_key).into_inner();
+        Some(value)
+    }
+
+    /// Resets the entry for `key` to expire after `timeout`.
+    ///
+    /// Returns `false` if there is no entry for `key`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics under the same conditions as [`DelayQueue::reset`].
+    #[track_caller]
+    pub fn reset<Q>(&mut self, key: &Q, timeout: Duration) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.reset_at(key, saturating_add(D::Instant::now(), timeout))
+    }
+
+    /// Resets the entry for `key` to expire at `when`.
+    ///
+    /// Returns `false` if there is no entry for `key`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics under the same conditions as [`DelayQueue::reset_at`].
+    #[track_caller]
+    pub fn reset_at<Q>(&mut self, key: &Q, when: D::Instant) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.keys.get(key) {
+            Some(queue_key) => {
+                self.queue.reset_at(queue_key, when);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns a reference to the value for `key`.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let queue_key = self.keys.get(key)?;
+        self.queue.get(queue_key).map(|(_, value)| value)
+    }
+
+    /// Returns a mutable reference to the value for `key`.
+    ///
+    /// The entry stays scheduled with its current deadline.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let queue_key = self.keys.get(key)?;
+        self.queue.get_mut(queue_key).map(|(_, value)| value)
+    }
+
+    /// Returns the instant at which the entry for `key` expires.
+    pub fn deadline<Q>(&self, key: &Q) -> Option<D::Instant>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let queue_key = self.keys.get(key)?;
+        self.queue.deadline(queue_key)
+    }
+
+    /// Returns `true` if the map has an entry for `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.keys.contains_key(key)
+    }
+
+    /// Returns an iterator over the entries of the map, along with their
+    /// deadlines, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V, D::Instant)> + '_ {
+        self.queue
+            .iter()
+            .map(|(_, (key, value), deadline)| (key, value, deadline))
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if there are no entries in the map.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Removes all entries from the map.
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.keys.clear();
+    }
+
+    /// Attempts to pull out the next expired entry of the map.
+    ///
+    /// This behaves like [`DelayQueue::poll_expired`], and yields the key and
+    /// the value of the entry. `Poll::Ready(None)` is returned when the map is
+    /// empty.
+    pub fn poll_expired(&mut self, cx: &mut task::Context<'_>) -> Poll<Option<(K, V)>> {
+        let expired = futures_util::ready!(self.queue.poll_expired(cx));
+        Poll::Ready(expired.map(|expired| {
+            let (key, value) = expired.into_inner();
+            self.keys.remove(&key);
+            (key, value)
+        }))
+    }
+}
+
+// We never put `K` or `V` in a `Pin`...
+impl<D, K, V> Unpin for DelayMap<D, K, V> where D: Delay {}
+
+impl<D, K, V> Default for DelayMap<D, K, V>
+where
+    D: Delay,
+    D::Instant: Unpin,
+    K: Hash + Eq + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D, K, V> fmt::Debug for DelayMap<D, K, V>
+where
+    D: Delay + fmt::Debug,
+    D::Instant: fmt::Debug,
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DelayMap")
+            .field("queue", &self.queue)
+            .field("len", &self.keys.len())
+            .finish()
+    }
+}
+
+impl<D, K, V> futures_util::Stream for DelayMap<D, K, V>
+where
+    D: Delay,
+    D::Instant: Unpin,
+    K: Hash + Eq + Clone,
+{
+    type Item = (K, V);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        DelayMap::poll_expired(self.get_mut(), cx)
+    }
+}
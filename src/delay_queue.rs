@@ -6,18 +6,21 @@
 
 use futures_util::ready;
 
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
 use core::ops::{Index, IndexMut};
 use slab::Slab;
-use std::cmp;
-use std::collections::HashMap;
-use std::convert::From;
-use std::fmt;
-use std::fmt::Debug;
-use std::future::Future;
-use std::marker::PhantomData;
-use std::pin::Pin;
-use std::task::{self, Poll, Waker};
-
+use core::cmp;
+use core::convert::From;
+use core::fmt;
+use core::fmt::Debug;
+use core::future::Future;
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{self, Poll, Waker};
+
+use crate::error::DelayQueueError;
 use crate::wheel::{self, Wheel};
 use crate::{Duration, Instant, Sleep, Delay};
 
@@ -51,9 +54,11 @@ use crate::{Duration, Instant, Sleep, Delay};
 /// returning items or `Poll::Pending`.
 ///
 /// Items are returned ordered by their expirations. Items that are configured
-/// to expire first will be returned first. There are no ordering guarantees
-/// for items configured to expire at the same instant. Also note that delays are
-/// rounded to the closest millisecond.
+/// to expire first will be returned first. By default, there are no ordering
+/// guarantees for items configured to expire at the same instant, see
+/// [`ExpirationOrder`] to return them in insertion order. Also note that delays are
+/// rounded up to the resolution of the queue, which is 1 millisecond by default
+/// and can be configured with [`DelayQueueBuilder::resolution`].
 ///
 /// # Implementation
 ///
@@ -107,10 +112,245 @@ pub struct DelayQueue<D: Delay, T> {
     /// Instant at which the timer starts
     start: D::Instant,
 
+    /// Duration of a single tick of the wheel
+    resolution: Duration,
+
     /// Waker that is invoked when we potentially need to reset the timer.
     /// Because we lazily create the timer when the first entry is created, we
     /// need to awaken any poller that polled us before that point.
     waker: Option<Waker>,
+
+    /// How deadlines beyond the range of the wheel are handled
+    far_deadline_behavior: FarDeadlineBehavior,
+
+    /// How long after the next deadline the timer may fire
+    slack: Duration,
+
+    /// Coalescer driving the timer, if any
+    #[cfg(feature = "std")]
+    coalescer: Option<crate::Coalescer<D>>,
+}
+
+/// Defines how a [`DelayQueue`] handles deadlines that are too far in the future for its timer
+/// wheel, which can track deadlines up to 63 × 2^30 ticks ahead, just under 2^36 (roughly 2 years
+/// with the default resolution of 1 millisecond).
+///
+/// # Default
+///
+/// The default behavior is [`FarDeadlineBehavior::Overflow`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FarDeadlineBehavior {
+    /// Keeps the entry in an overflow bucket sorted by deadline, and moves it into the wheel
+    /// once the deadline comes within range. Any deadline representable by the instant type is
+    /// accepted.
+    #[default]
+    Overflow,
+
+    /// Rejects the deadline. The fallible methods such as [`DelayQueue::try_insert_at`] return
+    /// [`DelayQueueError::DeadlineOutOfRange`] and the other methods panic.
+    Reject,
+
+    /// Clamps the deadline to the furthest deadline the wheel can track.
+    Clamp,
+}
+
+/// Defines the order in which a [`DelayQueue`] yields values that expire at
+/// the same instant, after rounding to the resolution of the queue.
+///
+/// # Default
+///
+/// The default is [`ExpirationOrder::Unordered`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExpirationOrder {
+    /// There are no ordering guarantees for values that expire at the same
+    /// instant. This is the fastest option.
+    #[default]
+    Unordered,
+
+    /// Values that expire at the same instant are yielded by descending
+    /// priority, and values of equal priority in the order they were inserted.
+    ///
+    /// A value that is reset keeps its original insertion order. Inserting a
+    /// value that must be yielded before many values expiring at the same
+    /// instant is linear in the number of those values.
+    Fifo,
+}
+
+/// Builder for a [`DelayQueue`].
+///
+/// # Example
+///
+/// Creates a queue of cache entries with a resolution of 1 second.
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use timer_kit::DelayQueue;
+///
+/// # #[cfg(feature = "smol")]
+/// # fn example() {
+/// let queue: DelayQueue<smol::Timer, &str> = DelayQueue::builder()
+///     .capacity(1024)
+///     .resolution(Duration::from_secs(1))
+///     .build();
+/// # }
+/// ```
+pub struct DelayQueueBuilder<D: Delay, T> {
+    capacity: usize,
+    resolution: Duration,
+    far_deadline_behavior: FarDeadlineBehavior,
+    expiration_order: ExpirationOrder,
+    slack: Option<Duration>,
+    #[cfg(feature = "std")]
+    coalescer: Option<crate::Coalescer<D>>,
+    _p: PhantomData<fn() -> (D, T)>,
+}
+
+impl<D, T> DelayQueueBuilder<D, T>
+where
+    D: Delay,
+{
+    /// Creates a new builder with the default configuration, which has no
+    /// pre-allocated capacity and a resolution of 1 millisecond.
+    pub fn new() -> Self {
+        Self {
+            capacity: 0,
+            resolution: DEFAULT_RESOLUTION,
+            far_deadline_behavior: FarDeadlineBehavior::default(),
+            expiration_order: ExpirationOrder::default(),
+            slack: None,
+            #[cfg(feature = "std")]
+            coalescer: None,
+            _p: PhantomData,
+        }
+    }
+
+    /// Sets the number of entries the queue can hold without reallocating.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the duration of a single tick of the queue.
+    ///
+    /// Deadlines are rounded up to a multiple of the resolution. A coarse
+    /// resolution wakes the task up less often, and a fine resolution is more
+    /// accurate.
+    ///
+    /// The wheel covers 63 × 2^30 ticks ahead of the current instant, so the
+    /// range of deadlines it can track scales with the resolution:
+    ///
+    /// | Resolution | Range |
+    /// | ---------- | ----- |
+    /// | 1 µs | about 19 hours |
+    /// | 1 ms (default) | about 2 years |
+    /// | 1 s | about 2000 years |
+    ///
+    /// Deadlines beyond that range are handled according to the
+    /// [`FarDeadlineBehavior`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `resolution` is zero.
+    #[track_caller]
+    pub fn resolution(mut self, resolution: Duration) -> Self {
+        assert!(resolution > Duration::new(0, 0), "resolution must be non-zero");
+        self.resolution = resolution;
+        self
+    }
+
+    /// Sets the [`FarDeadlineBehavior`] of the queue.
+    pub fn far_deadline_behavior(mut self, behavior: FarDeadlineBehavior) -> Self {
+        self.far_deadline_behavior = behavior;
+        self
+    }
+
+    /// Sets the [`ExpirationOrder`] of the values that expire at the same
+    /// instant.
+    pub fn expiration_order(mut self, order: ExpirationOrder) -> Self {
+        self.expiration_order = order;
+        self
+    }
+
+    /// Sets the slack of the timer of the queue, which is how long after the next deadline the
+    /// queue may wake up.
+    ///
+    /// See [`DelayQueue::set_slack`] for more details.
+    pub fn slack(mut self, slack: Duration) -> Self {
+        self.slack = Some(slack);
+        self
+    }
+
+    /// Drives the timer of the queue with `coalescer`, so that it fires together with the other
+    /// timers of the coalescer whose slack windows overlap.
+    ///
+    /// The queue uses the slack of the coalescer unless [`slack`](Self::slack) is set.
+    #[cfg(feature = "std")]
+    pub fn coalescer(mut self, coalescer: &crate::Coalescer<D>) -> Self {
+        self.coalescer = Some(coalescer.clone());
+        self
+    }
+
+    /// Creates the configured [`DelayQueue`].
+    pub fn build(self) -> DelayQueue<D, T> {
+        #[cfg(feature = "std")]
+        let default_slack = self.coalescer.as_ref().map_or(Duration::ZERO, |c| c.slack());
+        #[cfg(not(feature = "std"))]
+        let default_slack = Duration::ZERO;
+
+        DelayQueue {
+            wheel: Wheel::new(),
+            slab: SlabStorage::with_capacity(self.capacity, self.expiration_order),
+            expired: Stack::default(),
+            delay: None,
+            wheel_now: 0,
+            start: Instant::now(),
+            resolution: self.resolution,
+            waker: None,
+            far_deadline_behavior: self.far_deadline_behavior,
+            slack: self.slack.unwrap_or(default_slack),
+            #[cfg(feature = "std")]
+            coalescer: self.coalescer,
+        }
+    }
+}
+
+impl<D, T> Default for DelayQueueBuilder<D, T>
+where
+    D: Delay,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: Delay, T> Clone for DelayQueueBuilder<D, T> {
+    fn clone(&self) -> Self {
+        Self {
+            capacity: self.capacity,
+            resolution: self.resolution,
+            far_deadline_behavior: self.far_deadline_behavior,
+            expiration_order: self.expiration_order,
+            slack: self.slack,
+            #[cfg(feature = "std")]
+            coalescer: self.coalescer.clone(),
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<D: Delay, T> Debug for DelayQueueBuilder<D, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("DelayQueueBuilder");
+        debug
+            .field("capacity", &self.capacity)
+            .field("resolution", &self.resolution)
+            .field("far_deadline_behavior", &self.far_deadline_behavior)
+            .field("expiration_order", &self.expiration_order)
+            .field("slack", &self.slack);
+        #[cfg(feature = "std")]
+        debug.field("coalescer", &self.coalescer);
+        debug.finish()
+    }
 }
 
 #[derive(Default)]
@@ -120,32 +360,43 @@ struct SlabStorage<T> {
     // A `compact` call requires a re-mapping of the `Key`s that were changed
     // during the `compact` call of the `slab`. Since the keys that were given out
     // cannot be changed retroactively we need to keep track of these re-mappings.
-    // The keys of `key_map` correspond to the old keys that were given out and
-    // the values to the `Key`s that were re-mapped by the `compact` call.
-    key_map: HashMap<Key, KeyInternal>,
+    // The keys of `key_map` correspond to the indices of the old keys that were
+    // given out and the values to the `Key`s that were re-mapped by the `compact` call.
+    key_map: BTreeMap<usize, KeyInternal>,
 
     // Index used to create new keys to hand out.
     next_key_index: usize,
 
+    // Generation of the next key to hand out. Every key gets a distinct
+    // generation so that a stale key is not mistaken for a key that reuses its
+    // index.
+    next_generation: u64,
+
     // Whether `compact` has been called, necessary in order to decide whether
     // to include keys in `key_map`.
     compact_called: bool,
+
+    // Order of the entries with the same deadline in the stacks
+    order: ExpirationOrder,
 }
 
 impl<T> SlabStorage<T> {
-    pub(crate) fn with_capacity(capacity: usize) -> SlabStorage<T> {
+    pub(crate) fn with_capacity(capacity: usize, order: ExpirationOrder) -> SlabStorage<T> {
         SlabStorage {
             inner: Slab::with_capacity(capacity),
-            key_map: HashMap::new(),
+            key_map: BTreeMap::new(),
             next_key_index: 0,
+            next_generation: 0,
             compact_called: false,
+            order,
         }
     }
 
     // Inserts data into the inner slab and re-maps keys if necessary
-    pub(crate) fn insert(&mut self, val: Data<T>) -> Key {
-        let mut key = KeyInternal::new(self.inner.insert(val));
-        let key_contained = self.key_map.contains_key(&key.into());
+    pub(crate) fn insert(&mut self, inner: T, when: u64, priority: u32) -> Key {
+        let index = self.inner.vacant_key();
+        let mut key = KeyInternal::new(index);
+        let key_contained = self.key_map.contains_key(&key.index);
 
         if key_contained {
             // It's possible that a `compact` call creates capacity in `self.inner` in
@@ -155,16 +406,31 @@ impl<T> SlabStorage<T> {
             // We need to create a new key `key_to_give_out` and include the relation
             // `key_to_give_out` -> `key` in `self.key_map`.
             let key_to_give_out = self.create_new_key();
-            assert!(!self.key_map.contains_key(&key_to_give_out.into()));
-            self.key_map.insert(key_to_give_out.into(), key);
+            assert!(!self.key_map.contains_key(&key_to_give_out.index));
+            self.key_map.insert(key_to_give_out.index, key);
             key = key_to_give_out;
         } else if self.compact_called {
             // Include an identity mapping in `self.key_map` in order to allow us to
             // panic if a key that was handed out is removed more than once.
-            self.key_map.insert(key.into(), key);
+            self.key_map.insert(key.index, key);
         }
 
-        key.into()
+        // The `Key` handed out is stored alongside the data so that entries can
+        // be iterated over with their keys, and so that stale keys can be detected.
+        let key_out = Key::new(key.index, self.next_generation);
+        self.next_generation = self.next_generation.wrapping_add(1);
+        let inserted = self.inner.insert(Data {
+            inner,
+            when,
+            priority,
+            key: key_out,
+            expired: false,
+            next: None,
+            prev: None,
+        });
+        debug_assert_eq!(inserted, index);
+
+        key_out
     }
 
     // Re-map the key in case compact was previously called.
@@ -183,9 +449,17 @@ impl<T> SlabStorage<T> {
     // If we were to imply from this that no re-mapping was necessary, we would
     // incorrectly remove 1 from `self.slab.inner`, which corresponds to the
     // handed-out key 2.
+    //
+    // A stale key whose index has been handed out again is detected by its
+    // generation, which does not match the generation of the stored entry.
+    #[track_caller]
     pub(crate) fn remove(&mut self, key: &Key) -> Data<T> {
+        if !self.contains(key) {
+            panic!("invalid key");
+        }
+
         let remapped_key = if self.compact_called {
-            match self.key_map.remove(key) {
+            match self.key_map.remove(&key.index) {
                 Some(key_internal) => key_internal,
                 None => panic!("invalid key"),
             }
@@ -198,17 +472,16 @@ impl<T> SlabStorage<T> {
 
     pub(crate) fn shrink_to_fit(&mut self) {
         self.inner.shrink_to_fit();
-        self.key_map.shrink_to_fit();
     }
 
     pub(crate) fn compact(&mut self) {
         if !self.compact_called {
             for (key, _) in self.inner.iter() {
-                self.key_map.insert(Key::new(key), KeyInternal::new(key));
+                self.key_map.insert(key, KeyInternal::new(key));
             }
         }
 
-        let mut remapping = HashMap::new();
+        let mut remapping = BTreeMap::new();
         self.inner.compact(|_, from, to| {
             remapping.insert(from, to);
             true
@@ -221,10 +494,6 @@ impl<T> SlabStorage<T> {
             }
         }
 
-        if self.key_map.capacity() > 2 * self.key_map.len() {
-            self.key_map.shrink_to_fit();
-        }
-
         self.compact_called = true;
     }
 
@@ -233,14 +502,14 @@ impl<T> SlabStorage<T> {
     fn remap_key(&self, key: &Key) -> Option<KeyInternal> {
         let key_map = &self.key_map;
         if self.compact_called {
-            key_map.get(key).copied()
+            key_map.get(&key.index).copied()
         } else {
             Some((*key).into())
         }
     }
 
     fn create_new_key(&mut self) -> KeyInternal {
-        while self.key_map.contains_key(&Key::new(self.next_key_index)) {
+        while self.key_map.contains_key(&self.next_key_index) {
             self.next_key_index = self.next_key_index.wrapping_add(1);
         }
 
@@ -263,10 +532,6 @@ impl<T> SlabStorage<T> {
 
     pub(crate) fn reserve(&mut self, additional: usize) {
         self.inner.reserve(additional);
-
-        if self.compact_called {
-            self.key_map.reserve(additional);
-        }
     }
 
     pub(crate) fn is_empty(&self) -> bool {
@@ -274,12 +539,33 @@ impl<T> SlabStorage<T> {
     }
 
     pub(crate) fn contains(&self, key: &Key) -> bool {
-        let remapped_key = self.remap_key(key);
+        self.get(key).is_some()
+    }
 
-        match remapped_key {
-            Some(internal_key) => self.inner.contains(internal_key.index),
-            None => false,
-        }
+    // Entries with a smaller key are yielded first with `ExpirationOrder::Fifo`.
+    // The generation of the key increases with every insertion.
+    fn order_key(&self, key: Key) -> (u64, cmp::Reverse<u32>, u64) {
+        let data = &self[key];
+        (data.when, cmp::Reverse(data.priority), key.generation)
+    }
+
+    // Returns the entry of a `Key` that was given out to the user, or `None` if
+    // the key is stale.
+    fn get(&self, key: &Key) -> Option<&Data<T>> {
+        let internal_key = self.remap_key(key)?;
+
+        self.inner
+            .get(internal_key.index)
+            .filter(|data| data.key == *key)
+    }
+
+    // Mutable version of `get`
+    fn get_mut(&mut self, key: &Key) -> Option<&mut Data<T>> {
+        let internal_key = self.remap_key(key)?;
+
+        self.inner
+            .get_mut(internal_key.index)
+            .filter(|data| data.key == *key)
     }
 }
 
@@ -302,23 +588,21 @@ where
 impl<T> Index<Key> for SlabStorage<T> {
     type Output = Data<T>;
 
+    #[track_caller]
     fn index(&self, key: Key) -> &Self::Output {
-        let remapped_key = self.remap_key(&key);
-
-        match remapped_key {
-            Some(internal_key) => &self.inner[internal_key.index],
-            None => panic!("Invalid index {}", key.index),
+        match self.get(&key) {
+            Some(data) => data,
+            None => panic!("Invalid key {:?}", key),
         }
     }
 }
 
 impl<T> IndexMut<Key> for SlabStorage<T> {
+    #[track_caller]
     fn index_mut(&mut self, key: Key) -> &mut Data<T> {
-        let remapped_key = self.remap_key(&key);
-
-        match remapped_key {
-            Some(internal_key) => &mut self.inner[internal_key.index],
-            None => panic!("Invalid index {}", key.index),
+        match self.get_mut(&key) {
+            Some(data) => data,
+            None => panic!("Invalid key {:?}", key),
         }
     }
 }
@@ -340,16 +624,39 @@ pub struct Expired<T, I: Instant> {
     key: Key,
 }
 
+/// An iterator over the items of a [`DelayQueue`].
+///
+/// This is created by [`DelayQueue::iter`].
+pub struct Iter<'a, T, I> {
+    inner: slab::Iter<'a, Data<T>>,
+    start: I,
+    resolution: Duration,
+}
+
+/// A mutable iterator over the items of a [`DelayQueue`].
+///
+/// This is created by [`DelayQueue::iter_mut`].
+pub struct IterMut<'a, T, I> {
+    inner: slab::IterMut<'a, Data<T>>,
+    start: I,
+    resolution: Duration,
+}
+
 /// Token to a value stored in a `DelayQueue`.
 ///
 /// Instances of `Key` are returned by [`DelayQueue::insert`]. See [`DelayQueue`]
 /// documentation for more details.
 ///
+/// A `Key` carries a generation in addition to the slot it refers to, so a key
+/// whose entry has been removed or has expired is never confused with a newer
+/// entry that reuses the same slot. Such a stale key is reported as invalid.
+///
 /// [`DelayQueue`]: struct@DelayQueue
 /// [`DelayQueue::insert`]: method@DelayQueue::insert
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Key {
     index: usize,
+    generation: u64,
 }
 
 // Whereas `Key` is given out to users that use `DelayQueue`, internally we use
@@ -364,6 +671,9 @@ struct KeyInternal {
 struct Stack<T> {
     /// Head of the stack
     head: Option<Key>,
+
+    /// Tail of the stack
+    tail: Option<Key>,
     _p: PhantomData<fn() -> T>,
 }
 
@@ -376,6 +686,12 @@ struct Data<T> {
     /// The instant at which the item is returned.
     when: u64,
 
+    /// Priority among the items returned at the same instant.
+    priority: u32,
+
+    /// The key handed out for the entry.
+    key: Key,
+
     /// Set to true when stored in the `expired` queue
     expired: bool,
 
@@ -389,6 +705,9 @@ struct Data<T> {
 /// Maximum number of entries the queue can handle
 const MAX_ENTRIES: usize = (1 << 30) - 1;
 
+/// Default duration of a single tick of the wheel
+const DEFAULT_RESOLUTION: Duration = Duration::from_millis(1);
+
 impl<D, T> DelayQueue<D, T> 
 where
     D: Delay,
@@ -400,7 +719,7 @@ where
     ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,ignore
     /// # use tokio_util::time::DelayQueue;
     /// let delay_queue: DelayQueue<u32> = DelayQueue::new();
     /// ```
@@ -434,14 +753,54 @@ where
     /// # }
     /// ```
     pub fn with_capacity(capacity: usize) -> DelayQueue<D, T> {
-        DelayQueue {
-            wheel: Wheel::new(),
-            slab: SlabStorage::with_capacity(capacity),
-            expired: Stack::default(),
-            delay: None,
-            wheel_now: 0,
-            start: Instant::now(),
-            waker: None,
+        DelayQueueBuilder::new().capacity(capacity).build()
+    }
+
+    /// Creates a [`DelayQueueBuilder`] to configure a new `DelayQueue`.
+    pub fn builder() -> DelayQueueBuilder<D, T> {
+        DelayQueueBuilder::new()
+    }
+
+    /// Returns the duration of a single tick of the queue.
+    ///
+    /// Deadlines are rounded up to a multiple of the resolution.
+    pub fn resolution(&self) -> Duration {
+        self.resolution
+    }
+
+    /// Returns the [`FarDeadlineBehavior`] of the queue.
+    pub fn far_deadline_behavior(&self) -> FarDeadlineBehavior {
+        self.far_deadline_behavior
+    }
+
+    /// Sets the [`FarDeadlineBehavior`] of the queue.
+    pub fn set_far_deadline_behavior(&mut self, behavior: FarDeadlineBehavior) {
+        self.far_deadline_behavior = behavior;
+    }
+
+    /// Returns the [`ExpirationOrder`] of the queue.
+    pub fn expiration_order(&self) -> ExpirationOrder {
+        self.slab.order
+    }
+
+    /// Returns the slack of the timer of the queue.
+    pub fn slack(&self) -> Duration {
+        self.slack
+    }
+
+    /// Sets the slack of the timer of the queue, which is how long after the next deadline the
+    /// queue may wake up.
+    ///
+    /// Values are still yielded in the order of their deadlines, but a value may be yielded up
+    /// to `slack` late. This lets the queue keep its timer armed when an earlier value is
+    /// inserted shortly before the next deadline, and lets a [`Coalescer`](crate::Coalescer)
+    /// group the timer with other timers.
+    ///
+    /// See [`Sleep::set_slack`] for more details.
+    pub fn set_slack(&mut self, slack: Duration) {
+        self.slack = slack;
+        if let Some(ref mut delay) = self.delay {
+            delay.set_slack(slack);
         }
     }
 
@@ -456,17 +815,18 @@ where
     /// reached, then `value` is immediately made available to poll.
     ///
     /// The return value represents the insertion and is used as an argument to
-    /// [`remove`] and [`reset`]. Note that [`Key`] is a token and is reused once
-    /// `value` is removed from the queue either by calling [`poll_expired`] after
-    /// `when` is reached or by calling [`remove`]. At this point, the caller
-    /// must take care to not use the returned [`Key`] again as it may reference
-    /// a different item in the queue.
+    /// [`remove`] and [`reset`]. Once `value` is removed from the queue either
+    /// by calling [`poll_expired`] after `when` is reached or by calling
+    /// [`remove`], the returned [`Key`] becomes stale. A stale key never
+    /// references a different item in the queue, and is reported as invalid.
     ///
     /// See [type] level documentation for more details.
     ///
     /// # Panics
     ///
-    /// This function panics if `when` is too far in the future.
+    /// This function panics if the queue is full or if `when` is too far in the
+    /// future and the [`FarDeadlineBehavior`] is [`FarDeadlineBehavior::Reject`].
+    /// See [`try_insert_at`] for a non-panicking alternative.
     ///
     /// # Examples
     ///
@@ -491,55 +851,434 @@ where
     /// [`poll_expired`]: method@Self::poll_expired
     /// [`remove`]: method@Self::remove
     /// [`reset`]: method@Self::reset
+    /// [`try_insert_at`]: method@Self::try_insert_at
     /// [`Key`]: struct@Key
     /// [type]: #
     #[track_caller]
     pub fn insert_at(&mut self, value: T, when: D::Instant) -> Key {
-        assert!(self.slab.len() < MAX_ENTRIES, "max entries exceeded");
+        match self.try_insert_at(value, when) {
+            Ok(key) => key,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Attempts to insert `value` into the queue set to expire at a specific
+    /// instant in time.
+    ///
+    /// This function is identical to [`insert_at`], but returns an error instead
+    /// of panicking.
+    ///
+    /// # Errors
+    ///
+    /// - [`DelayQueueError::CapacityExceeded`] if the queue already holds the
+    ///   maximum number of entries.
+    /// - [`DelayQueueError::DeadlineOutOfRange`] if `when` is too far in the
+    ///   future and the [`FarDeadlineBehavior`] is [`FarDeadlineBehavior::Reject`].
+    ///
+    /// [`insert_at`]: method@Self::insert_at
+    pub fn try_insert_at(&mut self, value: T, when: D::Instant) -> Result<Key, DelayQueueError> {
+        self.try_insert_at_with_priority(value, when, 0)
+    }
+
+    /// Inserts `value` with a `priority` into the queue set to expire at a
+    /// specific instant in time.
+    ///
+    /// With [`ExpirationOrder::Fifo`], values that expire at the same instant
+    /// are yielded by descending priority, and values of equal priority in
+    /// the order they were inserted. Values inserted without a priority have
+    /// a priority of zero. The priority is ignored with
+    /// [`ExpirationOrder::Unordered`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics under the same conditions as [`insert_at`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::{Duration, Instant};
+    /// use timer_kit::{DelayQueue, ExpirationOrder};
+    ///
+    /// # #[cfg(feature = "smol")]
+    /// # async fn example() {
+    /// use futures::StreamExt;
+    ///
+    /// let mut delay_queue: DelayQueue<smol::Timer, _> = DelayQueue::builder()
+    ///     .expiration_order(ExpirationOrder::Fifo)
+    ///     .build();
+    ///
+    /// let when = Instant::now() + Duration::from_millis(10);
+    /// delay_queue.insert_at("first", when);
+    /// delay_queue.insert_at("second", when);
+    /// delay_queue.insert_at_with_priority("urgent", when, 1);
+    ///
+    /// assert_eq!(delay_queue.next().await.unwrap().into_inner(), "urgent");
+    /// assert_eq!(delay_queue.next().await.unwrap().into_inner(), "first");
+    /// assert_eq!(delay_queue.next().await.unwrap().into_inner(), "second");
+    /// # }
+    /// ```
+    ///
+    /// [`insert_at`]: method@Self::insert_at
+    #[track_caller]
+    pub fn insert_at_with_priority(&mut self, value: T, when: D::Instant, priority: u32) -> Key {
+        match self.try_insert_at_with_priority(value, when, priority) {
+            Ok(key) => key,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Attempts to insert `value` with a `priority` into the queue set to
+    /// expire at a specific instant in time.
+    ///
+    /// This function is identical to [`insert_at_with_priority`], but returns
+    /// an error instead of panicking. See [`try_insert_at`] for the possible
+    /// errors.
+    ///
+    /// [`insert_at_with_priority`]: method@Self::insert_at_with_priority
+    /// [`try_insert_at`]: method@Self::try_insert_at
+    pub fn try_insert_at_with_priority(
+        &mut self,
+        value: T,
+        when: D::Instant,
+        priority: u32,
+    ) -> Result<Key, DelayQueueError> {
+        let (key, when) = self
+            .insert_without_delay(value, when, priority)
+            .map_err(|(_, err)| err)?;
+        self.schedule_delay(when);
+
+        Ok(key)
+    }
+
+    /// Inserts `value` with a `priority` into the queue set to expire after
+    /// the requested duration elapses.
+    ///
+    /// See [`insert_at_with_priority`] for how the priority is used.
+    ///
+    /// # Panics
+    ///
+    /// This function panics under the same conditions as [`insert`].
+    ///
+    /// [`insert`]: method@Self::insert
+    /// [`insert_at_with_priority`]: method@Self::insert_at_with_priority
+    #[track_caller]
+    pub fn insert_with_priority(&mut self, value: T, timeout: Duration, priority: u32) -> Key {
+        let when = crate::util::saturating_add(D::Instant::now(), timeout);
+        self.insert_at_with_priority(value, when, priority)
+    }
+
+    /// Inserts the values of `iter` into the queue, each set to expire at the
+    /// paired instant.
+    ///
+    /// This is equivalent to calling [`insert_at`] for every value, but the
+    /// underlying timer is only updated once.
+    ///
+    /// # Panics
+    ///
+    /// This function panics under the same conditions as [`insert_at`]. The
+    /// values inserted before the panic stay in the queue.
+    ///
+    /// [`insert_at`]: method@Self::insert_at
+    #[track_caller]
+    pub fn extend_at<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (T, D::Instant)>,
+    {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        self.slab.reserve(lower);
+
+        let mut earliest = None;
+        let mut result = Ok(());
+        for (value, when) in iter {
+            match self.insert_without_delay(value, when, 0) {
+                Ok((_, when)) => earliest = Some(earliest.map_or(when, |e: u64| e.min(when))),
+                Err((_, err)) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+
+        if let Some(when) = earliest {
+            self.schedule_delay(when);
+        }
+
+        if let Err(err) = result {
+            panic!("{}", err);
+        }
+    }
+
+    /// Retains only the values for which `f` returns `true`, removing the
+    /// others from the queue.
+    ///
+    /// `f` is called with the key, a mutable reference to the value and the
+    /// deadline of every value in the queue, in no particular order. The
+    /// underlying timer is only updated once.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use timer_kit::DelayQueue;
+    ///
+    /// # #[cfg(feature = "smol")]
+    /// # fn example() {
+    /// let mut delay_queue = DelayQueue::<smol::Timer, _>::new();
+    /// for i in 0..10 {
+    ///     delay_queue.insert(i, Duration::from_secs(i));
+    /// }
+    ///
+    /// delay_queue.retain(|_, value, _| *value % 2 == 0);
+    /// assert_eq!(delay_queue.len(), 5);
+    /// # }
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Key, &mut T, D::Instant) -> bool,
+    {
+        let removed: Vec<Key> = self
+            .iter_mut()
+            .filter_map(|(key, value, deadline)| (!f(key, value, deadline)).then_some(key))
+            .collect();
+
+        if removed.is_empty() {
+            return;
+        }
+
+        for key in removed {
+            self.remove_key(&key);
+            self.slab.remove(&key);
+        }
+
+        self.update_delay();
+    }
+
+    /// Reserves capacity for `additional` more values, or returns
+    /// [`DelayQueueError::CapacityExceeded`] if the queue cannot hold them.
+    #[cfg(feature = "serde")]
+    pub(crate) fn try_reserve(&mut self, additional: usize) -> Result<(), DelayQueueError> {
+        if additional > MAX_ENTRIES - self.slab.len() {
+            return Err(DelayQueueError::CapacityExceeded);
+        }
+        self.slab.reserve(additional);
+        Ok(())
+    }
+
+    /// Inserts a value into the slab and the wheel without updating the delay.
+    ///
+    /// Returns the key and the normalized deadline of the value, or gives the
+    /// value back if it cannot be inserted.
+    pub(crate) fn insert_without_delay(
+        &mut self,
+        value: T,
+        when: D::Instant,
+        priority: u32,
+    ) -> Result<(Key, u64), (T, DelayQueueError)> {
+        if self.slab.len() >= MAX_ENTRIES {
+            return Err((value, DelayQueueError::CapacityExceeded));
+        }
 
         // Normalize the deadline. Values cannot be set to expire in the past.
-        let when = self.normalize_deadline(when);
+        let when = match self.checked_deadline(when) {
+            Ok(when) => when,
+            Err(err) => return Err((value, err)),
+        };
 
         // Insert the value in the store
-        let key = self.slab.insert(Data {
-            inner: value,
-            when,
-            expired: false,
-            next: None,
-            prev: None,
-        });
-
+        let key = self.slab.insert(value, when, priority);
         self.insert_idx(when, key);
+        trace!(key = ?key, len = self.slab.len(), "delay queue insert");
 
-        // Set a new delay if the current's deadline is later than the one of the new item
-        let should_set_delay = if let Some(ref delay) = self.delay {
-            let current_exp = self.normalize_deadline(delay.deadline());
-            current_exp > when
-        } else {
-            true
-        };
+        Ok((key, when))
+    }
 
-        if should_set_delay {
+    /// Sets a new delay if the current's deadline is later than `when`
+    pub(crate) fn schedule_delay(&mut self, when: u64) {
+        if self.needs_delay_before(when) {
             if let Some(waker) = self.waker.take() {
                 waker.wake();
             }
 
-            let delay_time = self.start + Duration::from_millis(when);
-            if let Some(ref mut delay) = &mut self.delay {
-                delay.as_mut().reset(delay_time);
-            } else {
-                let delay = Sleep::new_until(delay_time);
-                self.delay = Some(Box::pin(delay));
-            }
+            self.set_delay_before(when);
         }
+    }
 
-        key
+    /// Returns `true` if the delay must be moved earlier to fire at `when`.
+    pub(crate) fn needs_delay_before(&self, when: u64) -> bool {
+        if let Some(ref delay) = self.delay {
+            let current_exp = self.normalize_deadline(delay.deadline());
+            current_exp > when
+        } else {
+            true
+        }
+    }
+
+    /// Moves the delay earlier to fire at `when` if necessary, without waking
+    /// the task polling the queue.
+    pub(crate) fn set_delay_before(&mut self, when: u64) {
+        if !self.needs_delay_before(when) {
+            return;
+        }
+
+        let delay_time = self.instant_at(when);
+        if let Some(ref mut delay) = &mut self.delay {
+            delay.as_mut().reset(delay_time);
+        } else {
+            self.delay = Some(Box::pin(self.new_sleep(delay_time)));
+        }
     }
 
     /// Attempts to pull out the next value of the delay queue, registering the
     /// current task for wakeup if the value is not yet available, and returning
     /// `None` if the queue is exhausted.
     pub fn poll_expired(&mut self, cx: &mut task::Context<'_>) -> Poll<Option<Expired<T, D::Instant>>> {
+        self.register_waker(cx);
+
+        let item = ready!(self.poll_idx(cx));
+        Poll::Ready(item.map(|key| {
+            let expired = self.take_expired(key);
+            trace!(count = 1, len = self.slab.len(), "delay queue expired");
+            expired
+        }))
+    }
+
+    /// Attempts to pull out up to `max` expired values of the delay queue at
+    /// once, appending them to `buf`.
+    ///
+    /// This is equivalent to calling [`poll_expired`] repeatedly, but the
+    /// underlying timer is only updated once per batch rather than once per
+    /// value, which is much faster when many values expire together.
+    ///
+    /// # Returns
+    ///
+    /// - `Poll::Ready(n)` with `n > 0` if `n` expired values were appended to
+    ///   `buf`. More values may still be available.
+    /// - `Poll::Pending` if no value has expired yet, in which case the current
+    ///   task is registered for wakeup.
+    /// - `Poll::Ready(0)` if the queue is exhausted or if `max` is zero.
+    ///
+    /// [`poll_expired`]: method@Self::poll_expired
+    pub fn poll_expired_batch(
+        &mut self,
+        cx: &mut task::Context<'_>,
+        buf: &mut Vec<Expired<T, D::Instant>>,
+        max: usize,
+    ) -> Poll<usize> {
+        use self::wheel::Stack;
+
+        if max == 0 {
+            return Poll::Ready(0);
+        }
+
+        self.register_waker(cx);
+
+        let mut count = 0;
+        while count < max {
+            let key = match self.expired.pop(&mut self.slab) {
+                Some(key) => Some(key),
+                None => self.wheel.poll(self.wheel_now, &mut self.slab),
+            };
+
+            if let Some(key) = key {
+                buf.push(self.take_expired(key));
+                count += 1;
+                continue;
+            }
+
+            // Nothing else is due as of `wheel_now`, so advance to the next
+            // deadline if it has been reached.
+            self.update_delay();
+            match self.delay {
+                Some(ref mut delay) => match Pin::new(&mut *delay).poll(cx) {
+                    Poll::Ready(_) => {
+                        let deadline = delay.deadline();
+                        self.wheel_now = self.ticks_at(deadline);
+                    }
+                    Poll::Pending => break,
+                },
+                None => break,
+            }
+        }
+
+        if count > 0 {
+            self.update_delay();
+            trace!(count, len = self.slab.len(), "delay queue expired");
+            Poll::Ready(count)
+        } else if self.delay.is_none() {
+            Poll::Ready(0)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Removes and returns all the values whose deadline has been reached as
+    /// of now, without waiting.
+    ///
+    /// The values are returned ordered by their expirations, and the
+    /// underlying timer is only updated once.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use timer_kit::DelayQueue;
+    ///
+    /// # #[cfg(feature = "smol")]
+    /// # async fn example() {
+    /// let mut delay_queue = DelayQueue::<smol::Timer, _>::new();
+    /// for i in 0..1000 {
+    ///     delay_queue.insert(i, Duration::from_millis(10));
+    /// }
+    ///
+    /// smol::Timer::after(Duration::from_millis(20)).await;
+    /// assert_eq!(delay_queue.drain_expired().len(), 1000);
+    /// # }
+    /// ```
+    pub fn drain_expired(&mut self) -> Vec<Expired<T, D::Instant>> {
+        use self::wheel::Stack;
+
+        let now = cmp::max(self.ticks_at(D::Instant::now()), self.wheel.elapsed());
+
+        let mut buf = Vec::new();
+        while let Some(key) = self.expired.pop(&mut self.slab) {
+            buf.push(self.take_expired(key));
+        }
+        loop {
+            // Step through the instants the wheel must be polled at so that
+            // far deadlines are moved into range before they are due.
+            match self.wheel.poll_at() {
+                Some(at) if at <= now => {
+                    if let Some(key) = self.wheel.poll(at, &mut self.slab) {
+                        buf.push(self.take_expired(key));
+                    }
+                }
+                _ => {
+                    self.wheel.poll(now, &mut self.slab);
+                    break;
+                }
+            }
+        }
+        self.wheel_now = now;
+
+        if !buf.is_empty() {
+            self.update_delay();
+            trace!(
+                count = buf.len(),
+                len = self.slab.len(),
+                "delay queue expired"
+            );
+
+            // The task polling the queue must observe the new deadline
+            if let Some(waker) = self.waker.take() {
+                waker.wake();
+            }
+        }
+
+        buf
+    }
+
+    fn register_waker(&mut self, cx: &mut task::Context<'_>) {
         if !self
             .waker
             .as_ref()
@@ -548,19 +1287,19 @@ where
         {
             self.waker = Some(cx.waker().clone());
         }
+    }
 
-        let item = ready!(self.poll_idx(cx));
-        Poll::Ready(item.map(|key| {
-            let data = self.slab.remove(&key);
-            debug_assert!(data.next.is_none());
-            debug_assert!(data.prev.is_none());
-
-            Expired {
-                key,
-                data: data.inner,
-                deadline: self.start + Duration::from_millis(data.when),
-            }
-        }))
+    /// Removes the entry of an expired key from the slab
+    fn take_expired(&mut self, key: Key) -> Expired<T, D::Instant> {
+        let data = self.slab.remove(&key);
+        debug_assert!(data.next.is_none());
+        debug_assert!(data.prev.is_none());
+
+        Expired {
+            key,
+            data: data.inner,
+            deadline: self.instant_at(data.when),
+        }
     }
 
     /// Inserts `value` into the queue set to expire after the requested duration
@@ -575,19 +1314,20 @@ where
     /// zero, then `value` is immediately made available to poll.
     ///
     /// The return value represents the insertion and is used as an
-    /// argument to [`remove`] and [`reset`]. Note that [`Key`] is a
-    /// token and is reused once `value` is removed from the queue
-    /// either by calling [`poll_expired`] after `timeout` has elapsed
-    /// or by calling [`remove`]. At this point, the caller must not
-    /// use the returned [`Key`] again as it may reference a different
-    /// item in the queue.
+    /// argument to [`remove`] and [`reset`]. Once `value` is removed from
+    /// the queue either by calling [`poll_expired`] after `timeout` has
+    /// elapsed or by calling [`remove`], the returned [`Key`] becomes
+    /// stale. A stale key never references a different item in the
+    /// queue, and is reported as invalid.
     ///
     /// See [type] level documentation for more details.
     ///
     /// # Panics
     ///
-    /// This function panics if `timeout` is greater than the maximum
-    /// duration supported by the timer in the current `Runtime`.
+    /// This function panics if the queue is full or if `timeout` is greater
+    /// than the maximum duration supported by the queue and the
+    /// [`FarDeadlineBehavior`] is [`FarDeadlineBehavior::Reject`]. See
+    /// [`try_insert`] for a non-panicking alternative.
     ///
     /// # Examples
     ///
@@ -611,11 +1351,24 @@ where
     /// [`poll_expired`]: method@Self::poll_expired
     /// [`remove`]: method@Self::remove
     /// [`reset`]: method@Self::reset
+    /// [`try_insert`]: method@Self::try_insert
     /// [`Key`]: struct@Key
     /// [type]: #
     #[track_caller]
     pub fn insert(&mut self, value: T, timeout: Duration) -> Key {
-        self.insert_at(value, D::Instant::now() + timeout)
+        self.insert_at(value, crate::util::saturating_add(D::Instant::now(), timeout))
+    }
+
+    /// Attempts to insert `value` into the queue set to expire after the
+    /// requested duration elapses.
+    ///
+    /// This function is identical to [`insert`], but returns an error instead
+    /// of panicking. See [`try_insert_at`] for the possible errors.
+    ///
+    /// [`insert`]: method@Self::insert
+    /// [`try_insert_at`]: method@Self::try_insert_at
+    pub fn try_insert(&mut self, value: T, timeout: Duration) -> Result<Key, DelayQueueError> {
+        self.try_insert_at(value, crate::util::saturating_add(D::Instant::now(), timeout))
     }
 
     #[track_caller]
@@ -630,7 +1383,6 @@ where
                 // The delay is already expired, store it in the expired queue
                 self.expired.push(key, &mut self.slab);
             }
-            Err((_, err)) => panic!("invalid deadline; err={:?}", err),
         }
     }
 
@@ -660,7 +1412,9 @@ where
     ///
     /// # Panics
     ///
-    /// The function panics if `key` is not contained by the queue.
+    /// The function panics if `key` is not contained by the queue, which
+    /// includes stale keys of items that were already removed. See
+    /// [`try_remove`] for a non-panicking alternative.
     ///
     /// # Examples
     ///
@@ -680,35 +1434,28 @@ where
     /// assert_eq!(*item.get_ref(), "foo");
     /// # }
     /// ```
+    ///
+    /// [`try_remove`]: method@Self::try_remove
     #[track_caller]
     pub fn remove(&mut self, key: &Key) -> Expired<T, D::Instant> {
-        let prev_deadline = self.next_deadline();
-
-        self.remove_key(key);
-        let data = self.slab.remove(key);
-
-        let next_deadline = self.next_deadline();
-        if prev_deadline != next_deadline {
-            match (next_deadline, &mut self.delay) {
-                (None, _) => self.delay = None,
-                (Some(deadline), Some(delay)) => delay.as_mut().reset(deadline),
-                (Some(deadline), None) => self.delay = Some(Box::pin(Sleep::new_until(deadline))),
-            }
-        }
-
-        Expired {
-            key: Key::new(key.index),
-            data: data.inner,
-            deadline: self.start + Duration::from_millis(data.when),
+        match self.try_remove(key) {
+            Ok(expired) => expired,
+            Err(err) => panic!("{}", err),
         }
     }
 
     /// Attempts to remove the item associated with `key` from the queue.
     ///
     /// Removes the item associated with `key`, and returns it along with the
-    /// `Instant` at which it would have expired, if it exists.
+    /// `Instant` at which it would have expired.
+    ///
+    /// This function is identical to [`remove`], but returns an error instead
+    /// of panicking.
+    ///
+    /// # Errors
     ///
-    /// Returns `None` if `key` is not in the queue.
+    /// - [`DelayQueueError::InvalidKey`] if `key` is not contained by the queue,
+    ///   which includes stale keys of items that were already removed.
     ///
     /// # Examples
     ///
@@ -723,23 +1470,156 @@ where
     /// let mut delay_queue = DelayQueue::new();
     /// let key = delay_queue.insert("foo", Duration::from_secs(5));
     ///
-    /// // The item is in the queue, `try_remove` returns `Some(Expired("foo"))`.
+    /// // The item is in the queue, `try_remove` returns `Ok(Expired("foo"))`.
     /// let item = delay_queue.try_remove(&key);
     /// assert_eq!(item.unwrap().into_inner(), "foo");
     ///
-    /// // The item is not in the queue anymore, `try_remove` returns `None`.
+    /// // The item is not in the queue anymore, `try_remove` returns an error.
     /// let item = delay_queue.try_remove(&key);
-    /// assert!(item.is_none());
+    /// assert!(item.is_err());
+    /// # }
+    /// ```
+    ///
+    /// [`remove`]: method@Self::remove
+    pub fn try_remove(&mut self, key: &Key) -> Result<Expired<T, D::Instant>, DelayQueueError> {
+        if !self.slab.contains(key) {
+            return Err(DelayQueueError::InvalidKey);
+        }
+
+        let prev_deadline = self.next_deadline();
+
+        self.remove_key(key);
+        let data = self.slab.remove(key);
+        trace!(key = ?key, len = self.slab.len(), "delay queue remove");
+
+        if prev_deadline != self.next_deadline() {
+            self.update_delay();
+        }
+
+        Ok(Expired {
+            key: *key,
+            data: data.inner,
+            deadline: self.instant_at(data.when),
+        })
+    }
+
+    /// Returns `true` if the item associated with `key` is in the queue.
+    pub fn contains(&self, key: &Key) -> bool {
+        self.slab.contains(key)
+    }
+
+    /// Returns a reference to the item associated with `key`, or `None` if
+    /// `key` is not in the queue.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use timer_kit::DelayQueue;
+    ///
+    /// # #[cfg(feature = "smol")]
+    /// # fn example() {
+    /// let mut delay_queue = DelayQueue::<smol::Timer, _>::new();
+    /// let key = delay_queue.insert("foo", Duration::from_secs(5));
+    /// assert_eq!(delay_queue.get(&key), Some(&"foo"));
+    ///
+    /// delay_queue.remove(&key);
+    /// assert_eq!(delay_queue.get(&key), None);
     /// # }
     /// ```
-    pub fn try_remove(&mut self, key: &Key) -> Option<Expired<T, D::Instant>> {
+    pub fn get(&self, key: &Key) -> Option<&T> {
         if self.slab.contains(key) {
-            Some(self.remove(key))
+            Some(&self.slab[*key].inner)
         } else {
             None
         }
     }
 
+    /// Returns a mutable reference to the item associated with `key`, or
+    /// `None` if `key` is not in the queue.
+    ///
+    /// The item stays scheduled with its current deadline.
+    pub fn get_mut(&mut self, key: &Key) -> Option<&mut T> {
+        if self.slab.contains(key) {
+            Some(&mut self.slab[*key].inner)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the instant at which the item associated with `key` expires, or
+    /// `None` if `key` is not in the queue.
+    ///
+    /// The deadline is rounded up to the resolution of the queue, and is never
+    /// earlier than the last time the queue was polled.
+    pub fn deadline(&self, key: &Key) -> Option<D::Instant> {
+        if self.slab.contains(key) {
+            Some(self.instant_at(self.slab[*key].when))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the deadline of the item that expires next, or `None` if the
+    /// queue is empty.
+    ///
+    /// Unlike [`poll_expired`], this does not advance the queue, so items that
+    /// have already expired but have not been polled yet are included.
+    ///
+    /// [`poll_expired`]: method@Self::poll_expired
+    pub fn peek_next_deadline(&self) -> Option<D::Instant> {
+        use self::wheel::Stack;
+
+        // Entries in the `expired` queue expire no later than the entries in
+        // the wheel.
+        self.expired
+            .min_when(&self.slab)
+            .or_else(|| self.wheel.peek_min_when(&self.slab))
+            .map(|when| self.instant_at(when))
+    }
+
+    /// Returns an iterator over the items in the queue, along with their keys
+    /// and deadlines.
+    ///
+    /// The iterator yields `(Key, &T, D::Instant)` in no particular order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use timer_kit::DelayQueue;
+    ///
+    /// # #[cfg(feature = "smol")]
+    /// # fn example() {
+    /// let mut delay_queue = DelayQueue::<smol::Timer, _>::new();
+    /// delay_queue.insert("foo", Duration::from_secs(5));
+    /// delay_queue.insert("bar", Duration::from_secs(10));
+    ///
+    /// for (key, item, deadline) in delay_queue.iter() {
+    ///     println!("{:?}: {} expires at {:?}", key, item, deadline);
+    /// }
+    /// # }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T, D::Instant> {
+        Iter {
+            inner: self.slab.inner.iter(),
+            start: self.start,
+            resolution: self.resolution,
+        }
+    }
+
+    /// Returns an iterator that allows modifying the items in the queue.
+    ///
+    /// The iterator yields `(Key, &mut T, D::Instant)` in no particular order.
+    /// The deadlines of the items are not changed.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, D::Instant> {
+        IterMut {
+            inner: self.slab.inner.iter_mut(),
+            start: self.start,
+            resolution: self.resolution,
+        }
+    }
+
     /// Sets the delay of the item associated with `key` to expire at `when`.
     ///
     /// This function is identical to `reset` but takes an `Instant` instead of
@@ -751,8 +1631,10 @@ where
     ///
     /// # Panics
     ///
-    /// This function panics if `when` is too far in the future or if `key` is
-    /// not contained by the queue.
+    /// This function panics if `key` is not contained by the queue or if `when`
+    /// is too far in the future and the [`FarDeadlineBehavior`] is
+    /// [`FarDeadlineBehavior::Reject`]. See [`try_reset_at`] for a
+    /// non-panicking alternative.
     ///
     /// # Examples
     ///
@@ -774,23 +1656,84 @@ where
     /// // "foo" is now scheduled to be returned in 10 seconds
     /// # }
     /// ```
+    ///
+    /// [`try_reset_at`]: method@Self::try_reset_at
     #[track_caller]
     pub fn reset_at(&mut self, key: &Key, when: D::Instant) {
-        self.remove_key(key);
+        if let Err(err) = self.try_reset_at(key, when) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Attempts to set the delay of the item associated with `key` to expire
+    /// at `when`.
+    ///
+    /// This function is identical to [`reset_at`], but returns an error instead
+    /// of panicking.
+    ///
+    /// # Errors
+    ///
+    /// - [`DelayQueueError::InvalidKey`] if `key` is not contained by the queue.
+    /// - [`DelayQueueError::DeadlineOutOfRange`] if `when` is too far in the
+    ///   future and the [`FarDeadlineBehavior`] is [`FarDeadlineBehavior::Reject`].
+    ///
+    /// [`reset_at`]: method@Self::reset_at
+    pub fn try_reset_at(&mut self, key: &Key, when: D::Instant) -> Result<(), DelayQueueError> {
+        self.reset_without_delay(key, when)?;
+
+        let next_deadline = self.next_deadline();
+        if let (Some(ref mut delay), Some(deadline)) = (&mut self.delay, next_deadline) {
+            // This should awaken us if necessary (ie, if already expired)
+            delay.as_mut().reset(deadline);
+        }
+
+        Ok(())
+    }
+
+    /// Moves an entry to a new deadline in the wheel without updating the delay.
+    ///
+    /// Returns the normalized deadline of the entry.
+    pub(crate) fn reset_without_delay(&mut self, key: &Key, when: D::Instant) -> Result<u64, DelayQueueError> {
+        if !self.slab.contains(key) {
+            return Err(DelayQueueError::InvalidKey);
+        }
 
         // Normalize the deadline. Values cannot be set to expire in the past.
-        let when = self.normalize_deadline(when);
+        let when = self.checked_deadline(when)?;
+
+        self.remove_key(key);
 
         self.slab[*key].when = when;
         self.slab[*key].expired = false;
 
         self.insert_idx(when, *key);
 
-        let next_deadline = self.next_deadline();
-        if let (Some(ref mut delay), Some(deadline)) = (&mut self.delay, next_deadline) {
-            // This should awaken us if necessary (ie, if already expired)
-            delay.as_mut().reset(deadline);
+        Ok(when)
+    }
+
+    /// Removes an entry without updating the delay, which may then fire
+    /// earlier than necessary.
+    #[cfg(feature = "std")]
+    pub(crate) fn remove_without_delay(&mut self, key: &Key) -> Option<Expired<T, D::Instant>> {
+        if !self.slab.contains(key) {
+            return None;
         }
+
+        self.remove_key(key);
+        Some(self.take_expired(*key))
+    }
+
+    /// Returns the values of the queue along with their deadline and
+    /// priority, in the order a queue with [`ExpirationOrder::Fifo`] would
+    /// yield them.
+    #[cfg(feature = "serde")]
+    pub(crate) fn sorted_entries(&self) -> Vec<(&T, D::Instant, u32)> {
+        let mut entries: Vec<_> = self.slab.inner.iter().map(|(_, data)| data).collect();
+        entries.sort_by_key(|data| (data.when, cmp::Reverse(data.priority), data.key.generation));
+        entries
+            .into_iter()
+            .map(|data| (&data.inner, self.instant_at(data.when), data.priority))
+            .collect()
     }
 
     /// Shrink the capacity of the slab, which `DelayQueue` uses internally for storage allocation.
@@ -841,7 +1784,7 @@ where
     fn next_deadline(&mut self) -> Option<D::Instant> {
         self.wheel
             .poll_at()
-            .map(|poll_at| self.start + Duration::from_millis(poll_at))
+            .map(|poll_at| self.instant_at(poll_at))
     }
 
     /// Sets the delay of the item associated with `key` to expire after
@@ -856,8 +1799,10 @@ where
     ///
     /// # Panics
     ///
-    /// This function panics if `timeout` is greater than the maximum supported
-    /// duration or if `key` is not contained by the queue.
+    /// This function panics if `key` is not contained by the queue or if
+    /// `timeout` is greater than the maximum supported duration and the
+    /// [`FarDeadlineBehavior`] is [`FarDeadlineBehavior::Reject`]. See
+    /// [`try_reset`] for a non-panicking alternative.
     ///
     /// # Examples
     ///
@@ -879,9 +1824,23 @@ where
     /// // "foo"is now scheduled to be returned in 10 seconds
     /// # }
     /// ```
+    ///
+    /// [`try_reset`]: method@Self::try_reset
     #[track_caller]
     pub fn reset(&mut self, key: &Key, timeout: Duration) {
-        self.reset_at(key, D::Instant::now() + timeout);
+        self.reset_at(key, crate::util::saturating_add(D::Instant::now(), timeout));
+    }
+
+    /// Attempts to set the delay of the item associated with `key` to expire
+    /// after `timeout`.
+    ///
+    /// This function is identical to [`reset`], but returns an error instead
+    /// of panicking. See [`try_reset_at`] for the possible errors.
+    ///
+    /// [`reset`]: method@Self::reset
+    /// [`try_reset_at`]: method@Self::try_reset_at
+    pub fn try_reset(&mut self, key: &Key, timeout: Duration) -> Result<(), DelayQueueError> {
+        self.try_reset_at(key, crate::util::saturating_add(D::Instant::now(), timeout))
     }
 
     /// Clears the queue, removing all items.
@@ -1034,15 +1993,14 @@ where
         loop {
             if let Some(ref mut delay) = self.delay {
                 ready!(Pin::new(&mut *delay).poll(cx));
-                let now = crate::util::ms(delay.deadline() - self.start, crate::util::Round::Down);
-
-                self.wheel_now = now;
+                let deadline = delay.deadline();
+                self.wheel_now = self.ticks_at(deadline);
             }
 
             // We poll the wheel to get the next value out before finding the next deadline.
             let wheel_idx = self.wheel.poll(self.wheel_now, &mut self.slab);
 
-            self.delay = self.next_deadline().map(|when| Box::pin(Sleep::new_until(when)));
+            self.update_delay();
 
             if let Some(idx) = wheel_idx {
                 return Poll::Ready(Some(idx));
@@ -1054,15 +2012,67 @@ where
         }
     }
 
+    /// Resets the delay to the next deadline of the wheel, reusing the
+    /// existing `Sleep` if there is one.
+    fn update_delay(&mut self) {
+        match (self.next_deadline(), &mut self.delay) {
+            (None, _) => self.delay = None,
+            (Some(deadline), Some(delay)) => delay.as_mut().reset(deadline),
+            (Some(deadline), None) => self.delay = Some(Box::pin(self.new_sleep(deadline))),
+        }
+    }
+
+    /// Creates the timer of the queue with the slack and coalescer of the queue
+    fn new_sleep(&self, deadline: D::Instant) -> Sleep<D> {
+        #[cfg(feature = "std")]
+        if let Some(ref coalescer) = self.coalescer {
+            return Sleep::coalesced(coalescer.entry(), deadline, self.slack);
+        }
+
+        let mut sleep = Sleep::new_until(deadline);
+        sleep.set_slack(self.slack);
+        sleep
+    }
+
+    /// Number of whole ticks elapsed between the start of the queue and `when`
+    fn ticks_at(&self, when: D::Instant) -> u64 {
+        let elapsed = when.saturating_duration_since(self.start);
+        crate::util::ticks(elapsed, self.resolution, crate::util::Round::Down)
+    }
+
     fn normalize_deadline(&self, when: D::Instant) -> u64 {
-        let when = if when < self.start {
-            0
-        } else {
-            crate::util::ms(when - self.start, crate::util::Round::Up)
-        };
+        let elapsed = when.saturating_duration_since(self.start);
+        let when = crate::util::ticks(elapsed, self.resolution, crate::util::Round::Up);
 
         cmp::max(when, self.wheel.elapsed())
     }
+
+    /// Converts a number of ticks since the start of the queue to an instant
+    fn instant_at(&self, when: u64) -> D::Instant {
+        let elapsed = crate::util::duration_from_ticks(when, self.resolution);
+        crate::util::saturating_add(self.start, elapsed)
+    }
+
+    /// Normalizes the deadline and applies the [`FarDeadlineBehavior`] if it is
+    /// beyond the range of the wheel.
+    fn checked_deadline(&self, when: D::Instant) -> Result<u64, DelayQueueError> {
+        let when = self.normalize_deadline(when);
+
+        // The wheel is not advanced while the queue is idle, so measure the
+        // range from the current instant.
+        let now = cmp::max(self.wheel.elapsed(), self.ticks_at(D::Instant::now()));
+        let max = now.saturating_add(wheel::MAX_DURATION);
+
+        if when <= max {
+            return Ok(when);
+        }
+
+        match self.far_deadline_behavior {
+            FarDeadlineBehavior::Overflow => Ok(when),
+            FarDeadlineBehavior::Reject => Err(DelayQueueError::DeadlineOutOfRange),
+            FarDeadlineBehavior::Clamp => Ok(max),
+        }
+    }
 }
 
 // We never put `T` in a `Pin`...
@@ -1078,6 +2088,36 @@ where
     }
 }
 
+impl<D, T> Extend<(T, Duration)> for DelayQueue<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    /// Inserts the values of `iter` into the queue, each set to expire after
+    /// the paired duration elapses.
+    ///
+    /// See [`DelayQueue::extend_at`] for more details.
+    #[track_caller]
+    fn extend<I: IntoIterator<Item = (T, Duration)>>(&mut self, iter: I) {
+        let now = D::Instant::now();
+        let iter = iter.into_iter();
+        self.extend_at(iter.map(|(value, timeout)| (value, crate::util::saturating_add(now, timeout))));
+    }
+}
+
+impl<D, T> FromIterator<(T, Duration)> for DelayQueue<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    #[track_caller]
+    fn from_iter<I: IntoIterator<Item = (T, Duration)>>(iter: I) -> Self {
+        let mut queue = DelayQueue::new();
+        queue.extend(iter);
+        queue
+    }
+}
+
 impl<D, T> futures_util::Stream for DelayQueue<D, T> 
 where
     D: Delay,
@@ -1106,23 +2146,41 @@ impl<T> wheel::Stack for Stack<T> {
         debug_assert!(store[item].next.is_none());
         debug_assert!(store[item].prev.is_none());
 
-        // Remove the old head entry
-        let old = self.head.take();
+        // Without an ordering, the entry is pushed at the head. Otherwise, walk
+        // from the tail to find the last entry that is yielded before `item`.
+        // Entries are mostly inserted in order, so this is usually the tail.
+        let mut prev = None;
+        if store.order == ExpirationOrder::Fifo {
+            prev = self.tail;
+            while let Some(idx) = prev {
+                if store.order_key(idx) < store.order_key(item) {
+                    break;
+                }
+                prev = store[idx].prev;
+            }
+        }
+
+        let next = match prev {
+            Some(prev) => store[prev].next.replace(item),
+            None => self.head.replace(item),
+        };
 
-        if let Some(idx) = old {
-            store[idx].prev = Some(item);
+        match next {
+            Some(next) => store[next].prev = Some(item),
+            None => self.tail = Some(item),
         }
 
-        store[item].next = old;
-        self.head = Some(item);
+        store[item].prev = prev;
+        store[item].next = next;
     }
 
     fn pop(&mut self, store: &mut Self::Store) -> Option<Self::Owned> {
         if let Some(key) = self.head {
             self.head = store[key].next;
 
-            if let Some(idx) = self.head {
-                store[idx].prev = None;
+            match self.head {
+                Some(idx) => store[idx].prev = None,
+                None => self.tail = None,
             }
 
             store[key].next = None;
@@ -1161,6 +2219,8 @@ impl<T> wheel::Stack for Stack<T> {
 
         if let Some(next) = store[key].next {
             store[next].prev = store[key].prev;
+        } else {
+            self.tail = store[key].prev;
         }
 
         if let Some(prev) = store[key].prev {
@@ -1176,20 +2236,34 @@ impl<T> wheel::Stack for Stack<T> {
     fn when(item: &Self::Borrowed, store: &Self::Store) -> u64 {
         store[*item].when
     }
+
+    fn min_when(&self, store: &Self::Store) -> Option<u64> {
+        let mut next = self.head;
+        let mut min = None;
+
+        while let Some(idx) = next {
+            let data = &store[idx];
+            min = Some(min.map_or(data.when, |min: u64| min.min(data.when)));
+            next = data.next;
+        }
+
+        min
+    }
 }
 
 impl<T> Default for Stack<T> {
     fn default() -> Stack<T> {
         Stack {
             head: None,
+            tail: None,
             _p: PhantomData,
         }
     }
 }
 
 impl Key {
-    pub(crate) fn new(index: usize) -> Key {
-        Key { index }
+    pub(crate) fn new(index: usize, generation: u64) -> Key {
+        Key { index, generation }
     }
 }
 
@@ -1205,9 +2279,57 @@ impl From<Key> for KeyInternal {
     }
 }
 
-impl From<KeyInternal> for Key {
-    fn from(item: KeyInternal) -> Self {
-        Key::new(item.index)
+impl<'a, T, I: Instant> Iterator for Iter<'a, T, I> {
+    type Item = (Key, &'a T, I);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, data) = self.inner.next()?;
+        let elapsed = crate::util::duration_from_ticks(data.when, self.resolution);
+        let deadline = crate::util::saturating_add(self.start, elapsed);
+        Some((data.key, &data.inner, deadline))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T, I: Instant> ExactSizeIterator for Iter<'_, T, I> {}
+
+impl<T, I: Debug> Debug for Iter<'_, T, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Iter")
+            .field("remaining", &self.inner.len())
+            .field("start", &self.start)
+            .field("resolution", &self.resolution)
+            .finish()
+    }
+}
+
+impl<'a, T, I: Instant> Iterator for IterMut<'a, T, I> {
+    type Item = (Key, &'a mut T, I);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, data) = self.inner.next()?;
+        let elapsed = crate::util::duration_from_ticks(data.when, self.resolution);
+        let deadline = crate::util::saturating_add(self.start, elapsed);
+        Some((data.key, &mut data.inner, deadline))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T, I: Instant> ExactSizeIterator for IterMut<'_, T, I> {}
+
+impl<T, I: Debug> Debug for IterMut<'_, T, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IterMut")
+            .field("remaining", &self.inner.len())
+            .field("start", &self.start)
+            .field("resolution", &self.resolution)
+            .finish()
     }
 }
 
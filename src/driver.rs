@@ -0,0 +1,441 @@
+//! A timer driver that multiplexes many sleeps onto a single underlying delay.
+//!
+//! See [`TimerDriver`] for more details.
+
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, Weak},
+    task::{Context, Poll, Waker},
+    time::Duration,
+    vec::Vec,
+};
+
+use pin_project_lite::pin_project;
+
+use crate::{
+    error::Elapsed,
+    util::{duration_from_ticks, saturating_add, ticks, Round},
+    Delay, Instant, Sleep, TimerHandle, TimerWheel,
+};
+
+/// Default duration of a single tick of the wheel
+const DEFAULT_RESOLUTION: Duration = Duration::from_millis(1);
+
+/// Multiplexes many sleeps onto a single underlying [`Delay`].
+///
+/// Every [`Sleep`] owns a delay of type `D`, which can be expensive: `futures-timer` keeps every
+/// delay in a global heap served by a thread, and the wasm timers call `setTimeout` for each of
+/// them. A `TimerDriver` instead keeps the deadlines of its [`DriverSleep`]s and
+/// [`DriverTimeout`]s in a [`TimerWheel`], where registering and cancelling a deadline takes
+/// constant time, and only arms one delay for the earliest deadline.
+///
+/// The sleeps of a driver only complete while its [`Driver`] future, returned by
+/// [`TimerDriver::run`], is being polled, for example in a spawned task. The [`Driver`] completes
+/// once the `TimerDriver` and all of its sleeps have been dropped.
+///
+/// A `TimerDriver` is a cheap handle that can be cloned and shared across tasks and threads.
+/// Deadlines are rounded up to the resolution of the driver, which is 1 millisecond by default.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use timer_kit::TimerDriver;
+///
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
+/// let driver = TimerDriver::<smol::Timer>::new();
+/// smol::spawn(driver.run()).detach();
+///
+/// driver.sleep(Duration::from_millis(100)).await;
+///
+/// let result = driver.timeout(Duration::from_millis(100), async { 1 + 1 }).await;
+/// assert_eq!(result.unwrap(), 2);
+/// # }
+/// ```
+pub struct TimerDriver<D: Delay> {
+    shared: Arc<Mutex<State<D>>>,
+}
+
+/// The future that drives the sleeps of a [`TimerDriver`].
+///
+/// This is created by [`TimerDriver::run`], and completes once the [`TimerDriver`] and all of its
+/// sleeps have been dropped.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Driver<D: Delay> {
+    shared: Weak<Mutex<State<D>>>,
+}
+
+/// A future that completes at a deadline tracked by a [`TimerDriver`].
+///
+/// This is created by [`TimerDriver::sleep`] and [`TimerDriver::sleep_until`]. Dropping a
+/// `DriverSleep` cancels it.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct DriverSleep<D: Delay> {
+    shared: Arc<Mutex<State<D>>>,
+    handle: Option<TimerHandle>,
+    deadline: D::Instant,
+}
+
+pin_project! {
+    /// A future that requires a future to complete before a deadline tracked by a
+    /// [`TimerDriver`].
+    ///
+    /// This is created by [`TimerDriver::timeout`] and [`TimerDriver::timeout_at`].
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct DriverTimeout<D: Delay, Fut> {
+        sleep: DriverSleep<D>,
+
+        #[pin]
+        future: Fut,
+    }
+}
+
+struct State<D: Delay> {
+    /// Wakers of the pending sleeps, indexed by their deadline in ticks
+    wheel: TimerWheel<Option<Waker>>,
+
+    /// Instant at which the wheel starts
+    start: D::Instant,
+
+    /// Duration of a single tick of the wheel
+    resolution: Duration,
+
+    /// The single underlying delay, and the tick it is armed at
+    delay: Option<(Pin<Box<Sleep<D>>>, u64)>,
+
+    /// Waker of the task polling the driver
+    waker: Option<Waker>,
+}
+
+fn lock<D: Delay>(shared: &Mutex<State<D>>) -> MutexGuard<'_, State<D>> {
+    // The state is consistent even if a task panicked while holding the lock
+    shared.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+impl<D> TimerDriver<D>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    /// Creates a new `TimerDriver` with a resolution of 1 millisecond.
+    pub fn new() -> Self {
+        Self::with_resolution(DEFAULT_RESOLUTION)
+    }
+
+    /// Creates a new `TimerDriver` with the specified duration of a single tick.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `resolution` is zero.
+    #[track_caller]
+    pub fn with_resolution(resolution: Duration) -> Self {
+        assert!(resolution > Duration::new(0, 0), "resolution must be non-zero");
+
+        let state = State {
+            wheel: TimerWheel::new(),
+            start: D::Instant::now(),
+            resolution,
+            delay: None,
+            waker: None,
+        };
+
+        Self {
+            shared: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Returns the future that drives the sleeps of the driver.
+    ///
+    /// The future must be polled, usually in a spawned task, for the sleeps to complete. Only
+    /// one task should poll the futures returned by this method at a time.
+    pub fn run(&self) -> Driver<D> {
+        Driver {
+            shared: Arc::downgrade(&self.shared),
+        }
+    }
+
+    /// Creates a [`DriverSleep`] that completes after `duration`.
+    pub fn sleep(&self, duration: Duration) -> DriverSleep<D> {
+        self.sleep_until(saturating_add(D::Instant::now(), duration))
+    }
+
+    /// Creates a [`DriverSleep`] that completes at `deadline`.
+    pub fn sleep_until(&self, deadline: D::Instant) -> DriverSleep<D> {
+        let handle = lock(&self.shared).insert(deadline);
+
+        DriverSleep {
+            shared: self.shared.clone(),
+            handle: Some(handle),
+            deadline,
+        }
+    }
+
+    /// Requires `future` to complete within `duration`.
+    pub fn timeout<Fut>(&self, duration: Duration, future: Fut) -> DriverTimeout<D, Fut>
+    where
+        Fut: Future,
+    {
+        self.timeout_at(saturating_add(D::Instant::now(), duration), future)
+    }
+
+    /// Requires `future` to complete before `deadline`.
+    pub fn timeout_at<Fut>(&self, deadline: D::Instant, future: Fut) -> DriverTimeout<D, Fut>
+    where
+        Fut: Future,
+    {
+        DriverTimeout {
+            sleep: self.sleep_until(deadline),
+            future,
+        }
+    }
+
+    /// Returns the number of pending sleeps.
+    pub fn len(&self) -> usize {
+        lock(&self.shared).wheel.len()
+    }
+
+    /// Returns `true` if there are no pending sleeps.
+    pub fn is_empty(&self) -> bool {
+        lock(&self.shared).wheel.is_empty()
+    }
+}
+
+impl<D: Delay> State<D> {
+    fn insert(&mut self, deadline: D::Instant) -> TimerHandle {
+        let elapsed = deadline.saturating_duration_since(self.start);
+        let when = ticks(elapsed, self.resolution, Round::Up);
+        let handle = self.wheel.insert(when, None);
+
+        // The driver must arm the delay earlier
+        if self.delay.as_ref().is_none_or(|(_, armed)| when < *armed) {
+            if let Some(waker) = self.waker.take() {
+                waker.wake();
+            }
+        }
+        handle
+    }
+
+    fn ticks_at(&self, now: D::Instant) -> u64 {
+        ticks(now.saturating_duration_since(self.start), self.resolution, Round::Down)
+    }
+
+    fn instant_at(&self, when: u64) -> D::Instant {
+        saturating_add(self.start, duration_from_ticks(when, self.resolution))
+    }
+}
+
+impl<D: Delay> Drop for State<D> {
+    fn drop(&mut self) {
+        // Lets the driver complete once the last handle is dropped
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<D> Future for Driver<D>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let Some(shared) = self.shared.upgrade() else {
+            return Poll::Ready(());
+        };
+
+        let mut expired = Vec::new();
+        let mut state = lock(&shared);
+        state.waker = Some(cx.waker().clone());
+
+        let mut now = state.ticks_at(D::Instant::now());
+        loop {
+            expired.extend(state.wheel.advance(now).filter_map(|(_, waker)| waker));
+
+            let Some(when) = state.wheel.next_expiration() else {
+                state.delay = None;
+                break;
+            };
+
+            let deadline = state.instant_at(when);
+            let (delay, armed) = state
+                .delay
+                .get_or_insert_with(|| (Box::pin(Sleep::new_until(deadline)), when));
+            if *armed != when {
+                delay.as_mut().reset(deadline);
+                *armed = when;
+            }
+
+            match delay.as_mut().poll(cx) {
+                // The delay may fire slightly before `now` reaches its tick
+                Poll::Ready(_) => now = now.max(when),
+                Poll::Pending => break,
+            }
+        }
+        drop(state);
+
+        for waker in expired {
+            waker.wake();
+        }
+        Poll::Pending
+    }
+}
+
+impl<D> DriverSleep<D>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    /// Returns the deadline of the sleep.
+    pub fn deadline(&self) -> D::Instant {
+        self.deadline
+    }
+
+    /// Returns `true` if the deadline has been reached.
+    pub fn is_elapsed(&self) -> bool {
+        match self.handle {
+            Some(handle) => !lock(&self.shared).wheel.contains(handle) || D::Instant::now() >= self.deadline,
+            None => true,
+        }
+    }
+
+    /// Resets the sleep to complete at `deadline`.
+    pub fn reset(&mut self, deadline: D::Instant) {
+        let mut state = lock(&self.shared);
+        if let Some(handle) = self.handle.take() {
+            state.wheel.cancel(handle);
+        }
+        self.handle = Some(state.insert(deadline));
+        self.deadline = deadline;
+    }
+}
+
+impl<D> Future for DriverSleep<D>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        let Some(handle) = this.handle else {
+            return Poll::Ready(());
+        };
+
+        let mut state = lock(&this.shared);
+        let elapsed = D::Instant::now() >= this.deadline;
+        match state.wheel.get_mut(handle) {
+            Some(_) if elapsed => {
+                state.wheel.cancel(handle);
+            }
+            Some(waker) => {
+                match waker {
+                    Some(waker) if waker.will_wake(cx.waker()) => {}
+                    _ => *waker = Some(cx.waker().clone()),
+                }
+                return Poll::Pending;
+            }
+            // The driver has expired the sleep
+            None => {}
+        }
+
+        this.handle = None;
+        Poll::Ready(())
+    }
+}
+
+impl<D: Delay> Drop for DriverSleep<D> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            lock(&self.shared).wheel.cancel(handle);
+        }
+    }
+}
+
+impl<D, Fut> Future for DriverTimeout<D, Fut>
+where
+    D: Delay,
+    D::Instant: Unpin,
+    Fut: Future,
+{
+    type Output = Result<Fut::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        if let Poll::Ready(output) = this.future.poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+
+        match Pin::new(this.sleep).poll(cx) {
+            Poll::Ready(()) => Poll::Ready(Err(Elapsed::new())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<D> Default for TimerDriver<D>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: Delay> Clone for TimerDriver<D> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<D: Delay> fmt::Debug for TimerDriver<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = lock(&self.shared);
+        f.debug_struct("TimerDriver")
+            .field("resolution", &state.resolution)
+            .field("len", &state.wheel.len())
+            .finish()
+    }
+}
+
+impl<D: Delay> fmt::Debug for Driver<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Driver")
+            .field("closed", &(self.shared.strong_count() == 0))
+            .finish()
+    }
+}
+
+impl<D: Delay> fmt::Debug for DriverSleep<D>
+where
+    D::Instant: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DriverSleep")
+            .field("deadline", &self.deadline)
+            .field("pending", &self.handle.is_some())
+            .finish()
+    }
+}
+
+impl<D: Delay, Fut> fmt::Debug for DriverTimeout<D, Fut>
+where
+    D::Instant: fmt::Debug,
+    Fut: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DriverTimeout")
+            .field("sleep", &self.sleep)
+            .field("future", &self.future)
+            .finish()
+    }
+}
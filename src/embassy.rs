@@ -0,0 +1,176 @@
+//! [`Delay`](crate::Delay) and [`Instant`](crate::Instant) implementations backed by `embassy-time`.
+//!
+//! `embassy_time::Instant` only supports arithmetic with `embassy_time::Duration`, so this module
+//! provides thin wrappers around `embassy_time::Instant` and `embassy_time::Timer` that work with
+//! [`core::time::Duration`] as required by the [`Instant`](crate::Instant) and
+//! [`Delay`](crate::Delay) traits.
+//!
+//! Durations are converted to ticks of the `embassy-time` driver, rounding up so that a delay
+//! never completes early, and ticks are converted back to durations rounding down.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use core::time::Duration;
+//! use timer_kit::{embassy, Interval};
+//!
+//! # async fn example() {
+//! let mut interval = Interval::<embassy::Timer>::new(Duration::from_millis(100));
+//!
+//! interval.tick().await;
+//! interval.tick().await;
+//! # }
+//! ```
+
+use core::{
+    future::Future,
+    ops::{Add, Sub},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use ::embassy_time::TICK_HZ;
+
+use crate::util::saturating_add;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// An instant of the `embassy-time` driver's clock.
+///
+/// This wraps `embassy_time::Instant` and converts from and into it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Instant(::embassy_time::Instant);
+
+/// A [`Delay`](crate::Delay) backed by `embassy_time::Timer`.
+///
+/// The timer keeps its deadline so that [`Delay::deadline`](crate::Delay::deadline) is always
+/// available.
+#[derive(Debug)]
+pub struct Timer {
+    timer: ::embassy_time::Timer,
+    deadline: Instant,
+}
+
+impl Instant {
+    /// Returns the underlying `embassy_time::Instant`.
+    pub fn into_inner(self) -> ::embassy_time::Instant {
+        self.0
+    }
+}
+
+impl From<::embassy_time::Instant> for Instant {
+    fn from(instant: ::embassy_time::Instant) -> Self {
+        Self(instant)
+    }
+}
+
+impl From<Instant> for ::embassy_time::Instant {
+    fn from(instant: Instant) -> Self {
+        instant.0
+    }
+}
+
+impl crate::Instant for Instant {
+    fn now() -> Self {
+        Self(::embassy_time::Instant::now())
+    }
+
+    fn checked_add(self, duration: Duration) -> Option<Self> {
+        let duration = checked_to_embassy_duration(duration)?;
+        self.0.checked_add(duration).map(Self)
+    }
+
+    fn checked_sub(self, duration: Duration) -> Option<Self> {
+        let duration = checked_to_embassy_duration(duration)?;
+        self.0.checked_sub(duration).map(Self)
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Self;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        Self(self.0 + to_embassy_duration(rhs))
+    }
+}
+
+impl Sub<Duration> for Instant {
+    type Output = Self;
+
+    /// Returns the instant `rhs` before `self`, saturating at the driver's zero instant since the
+    /// clock starts at boot.
+    fn sub(self, rhs: Duration) -> Self::Output {
+        Self(self.0.saturating_sub(to_embassy_duration(rhs)))
+    }
+}
+
+impl Sub<Instant> for Instant {
+    type Output = Duration;
+
+    /// Returns the duration elapsed since `rhs`, or zero if `rhs` is later than `self`.
+    fn sub(self, rhs: Instant) -> Self::Output {
+        from_embassy_duration(self.0.saturating_duration_since(rhs.0))
+    }
+}
+
+impl crate::Delay for Timer {
+    type Value = ();
+    type Instant = Instant;
+
+    fn delay(duration: Duration) -> Self {
+        Self::delay_until(saturating_add(<Instant as crate::Instant>::now(), duration))
+    }
+
+    fn delay_until(deadline: Instant) -> Self {
+        Self {
+            timer: ::embassy_time::Timer::at(deadline.0),
+            deadline,
+        }
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        Some(self.deadline)
+    }
+
+    fn poll_elapsed(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Value> {
+        let me = self.get_mut();
+
+        // `embassy_time::Timer` always yields once before completing, even if its deadline has
+        // already passed.
+        if me.deadline.0 <= ::embassy_time::Instant::now() {
+            return Poll::Ready(());
+        }
+        Pin::new(&mut me.timer).poll(cx)
+    }
+
+    fn reset(self: Pin<&mut Self>, deadline: Instant) {
+        let me = self.get_mut();
+        *me = Self::delay_until(deadline);
+    }
+}
+
+/// Converts `duration` to a whole number of ticks, rounding up.
+///
+/// # Panics
+///
+/// Panics if the number of ticks does not fit in an `u64`.
+fn to_embassy_duration(duration: Duration) -> ::embassy_time::Duration {
+    checked_to_embassy_duration(duration).expect("duration is too long for embassy_time::Duration")
+}
+
+/// Converts `duration` to a whole number of ticks, rounding up, or returns `None` if the number of
+/// ticks does not fit in an `u64`.
+fn checked_to_embassy_duration(duration: Duration) -> Option<::embassy_time::Duration> {
+    let ticks = duration.as_nanos().checked_mul(TICK_HZ as u128)?.div_ceil(NANOS_PER_SEC);
+    u64::try_from(ticks).ok().map(::embassy_time::Duration::from_ticks)
+}
+
+/// Converts `duration` to a [`Duration`], rounding down to the nanosecond.
+fn from_embassy_duration(duration: ::embassy_time::Duration) -> Duration {
+    let nanos = duration.as_ticks() as u128 * NANOS_PER_SEC / TICK_HZ as u128;
+    Duration::new(
+        (nanos / NANOS_PER_SEC) as u64,
+        (nanos % NANOS_PER_SEC) as u32,
+    )
+}
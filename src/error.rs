@@ -12,10 +12,85 @@ impl Elapsed {
     }
 }
 
-impl std::fmt::Display for Elapsed {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "Timeout elapsed")
     }
 }
 
-impl std::error::Error for Elapsed {}
\ No newline at end of file
+impl core::error::Error for Elapsed {}
+
+/// Errors returned by the fallible operations on [`DelayQueue`](crate::DelayQueue), such as
+/// [`DelayQueue::try_insert_at`](crate::DelayQueue::try_insert_at) and
+/// [`DelayQueue::try_reset_at`](crate::DelayQueue::try_reset_at).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DelayQueueError {
+    /// The queue already holds the maximum number of entries.
+    CapacityExceeded,
+
+    /// The deadline is too far in the future to be tracked by the queue.
+    DeadlineOutOfRange,
+
+    /// The key does not refer to an entry in the queue.
+    InvalidKey,
+
+    /// The consumer of a [`SharedDelayQueue`](crate::SharedDelayQueue) has been dropped.
+    Closed,
+}
+
+impl core::fmt::Display for DelayQueueError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::CapacityExceeded => write!(f, "max entries exceeded"),
+            Self::DeadlineOutOfRange => write!(f, "deadline out of range"),
+            Self::InvalidKey => write!(f, "invalid key"),
+            Self::Closed => write!(f, "queue closed"),
+        }
+    }
+}
+
+impl core::error::Error for DelayQueueError {}
+
+cfg_std! {
+    /// Error returned by [`DelaySender::send_after`](crate::DelaySender::send_after) and
+    /// [`DelaySender::send_at`](crate::DelaySender::send_at) when the message cannot be scheduled.
+    ///
+    /// The message that failed to send is kept and can be recovered with
+    /// [`into_inner`](Self::into_inner).
+    pub struct DelaySendError<T> {
+        message: T,
+        error: DelayQueueError,
+    }
+
+    impl<T> DelaySendError<T> {
+        pub(crate) fn new(message: T, error: DelayQueueError) -> Self {
+            Self { message, error }
+        }
+
+        /// Returns the reason the message could not be sent.
+        pub fn error(&self) -> DelayQueueError {
+            self.error
+        }
+
+        /// Consumes the error, returning the message that failed to send.
+        pub fn into_inner(self) -> T {
+            self.message
+        }
+    }
+
+    impl<T> core::fmt::Debug for DelaySendError<T> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("DelaySendError")
+                .field("error", &self.error)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl<T> core::fmt::Display for DelaySendError<T> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "failed to send delayed message: {}", self.error)
+        }
+    }
+
+    impl<T> core::error::Error for DelaySendError<T> {}
+}
@@ -1,9 +1,15 @@
-mod std;
+cfg_std! {
+    mod std;
+}
 
 cfg_not_wasm32! {
     cfg_tokio! {
         mod tokio;
     }
+
+    cfg_quanta! {
+        mod quanta;
+    }
 }
 
 cfg_wasm32! {
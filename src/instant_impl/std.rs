@@ -1,7 +1,23 @@
-use std::time::Instant as StdInstant;
+use std::time::{Duration, Instant as StdInstant};
 
 impl crate::Instant for StdInstant {
     fn now() -> Self {
         StdInstant::now()
     }
+
+    fn checked_add(self, duration: Duration) -> Option<Self> {
+        StdInstant::checked_add(&self, duration)
+    }
+
+    fn checked_sub(self, duration: Duration) -> Option<Self> {
+        StdInstant::checked_sub(&self, duration)
+    }
+
+    fn duration_since(self, earlier: Self) -> Duration {
+        StdInstant::duration_since(&self, earlier)
+    }
+
+    fn saturating_duration_since(self, earlier: Self) -> Duration {
+        StdInstant::saturating_duration_since(&self, earlier)
+    }
 }
\ No newline at end of file
@@ -1,7 +1,25 @@
+use std::time::Duration;
+
 use tokio::time::Instant;
 
 impl crate::Instant for Instant {
     fn now() -> Self {
         Instant::now()
     }
+
+    fn checked_add(self, duration: Duration) -> Option<Self> {
+        Instant::checked_add(&self, duration)
+    }
+
+    fn checked_sub(self, duration: Duration) -> Option<Self> {
+        Instant::checked_sub(&self, duration)
+    }
+
+    fn duration_since(self, earlier: Self) -> Duration {
+        Instant::duration_since(&self, earlier)
+    }
+
+    fn saturating_duration_since(self, earlier: Self) -> Duration {
+        Instant::saturating_duration_since(&self, earlier)
+    }
 }
\ No newline at end of file
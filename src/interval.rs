@@ -1,8 +1,9 @@
-use std::{pin::Pin, time::{Duration}, task::{Context, Poll}, task::ready, future::poll_fn};
+use alloc::boxed::Box;
+use core::{pin::Pin, time::{Duration}, task::{Context, Poll}, task::ready, future::poll_fn};
 
 use futures_util::{Stream, Future};
 
-use crate::{Delay, Sleep};
+use crate::{util::saturating_add, Delay, Instant, Sleep};
 
 const BUFFER_TIMEOUT: Duration = Duration::from_millis(5);
 
@@ -22,11 +23,14 @@ const BUFFER_TIMEOUT: Duration = Duration::from_millis(5);
 /// use std::time::Duration;
 /// use timer_kit::interval;
 /// 
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
 /// let mut interval = interval::<smol::Timer>(Duration::from_millis(100));
 /// 
 /// interval.tick().await;
 /// interval.tick().await;
 /// interval.tick().await;
+/// # }
 /// ```
 /// 
 /// Creates an interval with `fluvio_wasm_timer::Delay`
@@ -35,11 +39,14 @@ const BUFFER_TIMEOUT: Duration = Duration::from_millis(5);
 /// use std::time::Duration;
 /// use timer_kit::interval;
 /// 
+/// # #[cfg(feature = "fluvio-wasm-timer")]
+/// # async fn example() {
 /// let mut interval = interval::<fluvio_wasm_timer::Delay>(Duration::from_millis(100));
 /// 
 /// interval.tick().await;
 /// interval.tick().await;
 /// interval.tick().await;
+/// # }
 /// ```
 pub fn interval<D>(duration: Duration) -> Interval<D> 
 where
@@ -65,25 +72,31 @@ where
 /// use std::time::{Duration, Instant};
 /// use timer_kit::interval_at;
 /// 
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
 /// let mut interval = interval_at::<smol::Timer>(Instant::now(), Duration::from_millis(100));
 /// 
 /// interval.tick().await;
 /// interval.tick().await;
 /// interval.tick().await;
+/// # }
 /// ```
 /// 
 /// Creates an interval with `fluvio_wasm_timer::Delay`
 /// 
 /// ```rust,no_run
 /// use std::time::{Duration};
-/// use fluvio_wasm_timer::Instant;
 /// use timer_kit::interval_at;
 /// 
+/// # #[cfg(feature = "fluvio-wasm-timer")]
+/// # async fn example() {
+/// use fluvio_wasm_timer::Instant;
 /// let mut interval = interval_at::<fluvio_wasm_timer::Delay>(Instant::now(), Duration::from_millis(100));
 /// 
 /// interval.tick().await;
 /// interval.tick().await;
 /// interval.tick().await;
+/// # }
 /// ```
 pub fn interval_at<D>(start: D::Instant, duration: Duration) -> Interval<D> 
 where
@@ -98,9 +111,10 @@ where
 /// # Default
 /// 
 /// The default behavior is [`MissedTickBehavior::Burst`].
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum MissedTickBehavior {
     /// Ticks as fast as possible until caught up.
+    #[default]
     Burst,
 
     /// Tick at multiples of `period` from when [`tick`] was called, rather than
@@ -118,35 +132,30 @@ impl MissedTickBehavior {
         D: Delay,
     {
         match self {
-            Self::Burst => timeout + period,
-            Self::Delay => now + period,
+            Self::Burst => saturating_add(timeout, period),
+            Self::Delay => saturating_add(now, period),
             Self::Skip => {
-                now + period
-                    - Duration::from_nanos(
-                        ((now - timeout).as_nanos() % period.as_nanos())
-                            .try_into()
-                            // This operation is practically guaranteed not to
-                            // fail, as in order for it to fail, `period` would
-                            // have to be longer than `now - timeout`, and both
-                            // would have to be longer than 584 years.
-                            //
-                            // If it did fail, there's not a good way to pass
-                            // the error along to the user, so we just panic.
-                            .expect(
-                                "too much time has elapsed since the interval was supposed to tick",
-                            ),
-                    )
+                let behind = now.saturating_duration_since(timeout).as_nanos() % period.as_nanos();
+                let behind = Duration::from_nanos(
+                    behind
+                        .try_into()
+                        // This operation is practically guaranteed not to
+                        // fail, as in order for it to fail, `period` would
+                        // have to be longer than `now - timeout`, and both
+                        // would have to be longer than 584 years.
+                        //
+                        // If it did fail, there's not a good way to pass
+                        // the error along to the user, so we just panic.
+                        .expect(
+                            "too much time has elapsed since the interval was supposed to tick",
+                        ),
+                );
+                saturating_add(now, period - behind)
             }
         }
     }
 }
 
-impl Default for MissedTickBehavior {
-    fn default() -> Self {
-        Self::Burst
-    }
-}
-
 /// An [`Interval`] allows you to wait on a sequence of instants with a certain duration between
 /// each instant. 
 /// 
@@ -159,12 +168,12 @@ pub struct Interval<D: Delay> {
     period: Duration,
 }
 
-impl<D> std::fmt::Debug for Interval<D>
+impl<D> core::fmt::Debug for Interval<D>
 where
-    D: Delay + std::fmt::Debug,
-    D::Instant: std::fmt::Debug,
+    D: Delay + core::fmt::Debug,
+    D::Instant: core::fmt::Debug,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Interval")
             .field("delay", &self.delay)
             .field("missed_tick_behavior", &self.missed_tick_behavior)
@@ -194,11 +203,14 @@ where
     /// use std::time::Duration;
     /// use timer_kit::Interval;
     /// 
+    /// # #[cfg(feature = "smol")]
+    /// # async fn example() {
     /// let mut interval = Interval::<smol::Timer>::new(Duration::from_millis(100));
     /// 
     /// interval.tick().await;
     /// interval.tick().await;
     /// interval.tick().await;
+    /// # }
     /// ```
     /// 
     /// Creates a new [`Interval`] that yields every 100 milliseconds with
@@ -208,11 +220,14 @@ where
     /// use std::time::Duration;
     /// use timer_kit::Interval;
     /// 
+    /// # #[cfg(feature = "fluvio-wasm-timer")]
+    /// # async fn example() {
     /// let mut interval = Interval::<fluvio_wasm_timer::Delay>::new(Duration::from_millis(100));
     /// 
     /// interval.tick().await;
     /// interval.tick().await;
     /// interval.tick().await;
+    /// # }
     /// ```
     pub fn new(period: Duration) -> Self {
         assert!(period > Duration::new(0, 0), "period must be non-zero");
@@ -239,11 +254,14 @@ where
     /// use std::time::{Duration, Instant};
     /// use timer_kit::Interval;
     /// 
+    /// # #[cfg(feature = "smol")]
+    /// # async fn example() {
     /// let mut interval = Interval::<smol::Timer>::new_at(Instant::now(), Duration::from_millis(100));
     /// 
     /// interval.tick().await;
     /// interval.tick().await;
     /// interval.tick().await;
+    /// # }
     /// ```
     /// 
     /// Creates a new [`Interval`] that yields every 100 milliseconds with
@@ -251,14 +269,17 @@ where
     /// 
     /// ```rust,no_run
     /// use std::time::Duration;
-    /// use fluvio_wasm_timer::Instant;
     /// use timer_kit::Interval;
     /// 
+    /// # #[cfg(feature = "fluvio-wasm-timer")]
+    /// # async fn example() {
+    /// use fluvio_wasm_timer::Instant;
     /// let mut interval = Interval::<fluvio_wasm_timer::Delay>::new_at(Instant::now(), Duration::from_millis(100));
     /// 
     /// interval.tick().await;
     /// interval.tick().await;
     /// interval.tick().await;
+    /// # }
     /// ```
     pub fn new_at(start: D::Instant, period: Duration) -> Self {
         assert!(period > Duration::new(0, 0), "period must be non-zero");
@@ -269,6 +290,17 @@ where
         }
     }
 
+    /// Creates an [`Interval`] that ticks when `sleep` completes and every `period` after that.
+    #[cfg(feature = "std")]
+    pub(crate) fn from_sleep(sleep: Sleep<D>, period: Duration) -> Self {
+        assert!(period > Duration::new(0, 0), "period must be non-zero");
+        Self {
+            delay: Box::pin(sleep),
+            missed_tick_behavior: MissedTickBehavior::default(),
+            period,
+        }
+    }
+
     /// Returns the [`MissedTickBehavior`] of the [`Interval`].
     pub fn missed_tick_behavior(&self) -> MissedTickBehavior {
         self.missed_tick_behavior
@@ -284,20 +316,40 @@ where
         self.period
     }
 
+    /// Returns the slack of the [`Interval`].
+    pub fn slack(&self) -> Duration {
+        self.delay.slack()
+    }
+
+    /// Sets the slack of the [`Interval`], which is how long after its scheduled instant a tick
+    /// may complete. The next ticks are still scheduled from the scheduled instant, so the slack
+    /// does not make the interval drift.
+    ///
+    /// See [`Sleep::set_slack`] for more details.
+    pub fn set_slack(&mut self, slack: Duration) {
+        self.delay.set_slack(slack);
+    }
+
     /// Polls the next tick of the [`Interval`].
     pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<D::Value> {
-        use crate::Instant;
-
         let value = ready!(self.delay.as_mut().poll(cx));
 
         let timeout = self.delay.deadline();
 
         let now = D::Instant::now();
 
-        let next = if now > timeout + BUFFER_TIMEOUT {
+        // A tick that completes within the slack is not late
+        let next = if now > saturating_add(timeout, self.delay.slack().saturating_add(BUFFER_TIMEOUT)) {
+            debug!(
+                lateness = ?now.saturating_duration_since(timeout),
+                missed = (now.saturating_duration_since(timeout).as_nanos()
+                    / self.period.as_nanos()) as u64,
+                behavior = ?self.missed_tick_behavior,
+                "interval missed ticks"
+            );
             self.missed_tick_behavior.next_timeout::<D>(timeout, now, self.period)
         } else {
-            timeout + self.period
+            saturating_add(timeout, self.period)
         };
 
         self.delay.as_mut().reset(next);
@@ -312,9 +364,7 @@ where
     /// Resets the interval to complete one period after the current time.
     /// This method ignores [`MissedTickBehavior`] strategy.
     pub fn reset(&mut self) {
-        use crate::Instant;
-
-        let deadline = D::Instant::now() + self.period;
+        let deadline = saturating_add(D::Instant::now(), self.period);
         self.delay.as_mut().reset(deadline);
     }
 }
@@ -1,3 +1,4 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs, missing_debug_implementations)]
 
 //! A timer toolkit that is generic over the underlying timer implementation. 
@@ -12,6 +13,16 @@
 //! 2. [`timeout()`]/[`Timeout`]
 //! 3. [`interval()`]/[`Interval`]
 //! 4. [`DelayQueue`]
+//! 5. [`DelayMap`]
+//! 6. [`SharedDelayQueue`]
+//! 7. [`delay_channel()`]/[`DelaySender`]/[`DelayReceiver`]
+//! 8. [`TtlCache`]
+//! 9. [`TimerWheel`]
+//! 10. [`blocking`] counterparts for synchronous code
+//! 11. [`Coalescer`]
+//! 12. [`TimerDriver`]
+//! 13. [`Watchdog`]/[`HeartbeatMonitor`]
+//! 14. [`Stopwatch`], [`measure()`] and [`ElapsedHistogram`]
 //! 
 //! This crate currently does not provide any feature beyond the ones that is already provided by
 //! `tokio`, so this crate is completely not needed if you are already using `tokio` in your
@@ -27,6 +38,29 @@
 //! | [`futures_timer::Delay`] | `"futures-timer"` | non-wasm32 |
 //! | [`wasm_timer::Delay`] | `"wasm-timer"` | wasm32 |
 //! | [`fluvio_wasm_timer::Delay`] | `"fluvio-wasm-timer"` | wasm32 |
+//! | [`embassy::Timer`] | `"embassy-time"` | any |
+//! | [`quanta::Timer`] | `"quanta"` | non-wasm32 |
+//!
+//! # `no_std` support
+//!
+//! The `"std"` feature is enabled by default. Without it, the crate is `no_std` and only requires
+//! `alloc`. [`Sleep`], [`Timeout`], [`Interval`], [`DelayQueue`], [`TimerWheel`] and [`Stopwatch`]
+//! are then available with any clock that implements the [`Instant`] and [`Delay`] traits, such as
+//! one backed by a hardware timer. The other tools require `"std"`, and so do all the timer
+//! implementations listed above except `"embassy-time"`, which wraps `embassy_time::Timer` and
+//! `embassy_time::Instant` for embedded targets.
+//!
+//! # Snapshots
+//!
+//! Enabling the `"serde"` feature adds `DelayQueue::snapshot` and `DelayQueue::restore` to save
+//! the pending entries of a queue and load them back after a restart.
+//!
+//! # Tracing
+//!
+//! Enabling the `"tracing"` feature emits `tracing` events when a `Sleep` is created, reset or
+//! elapses (with its lateness), when a `Timeout` elapses (with the label set by
+//! `Timeout::with_label`), when an `Interval` misses ticks, and when entries are inserted, removed or
+//! expire from a `DelayQueue` (with the number of entries). The feature does not require `"std"`.
 //!
 //! # WebAssembly support
 //! 
@@ -40,7 +74,9 @@
 //! type parameter `D` which is the type of the underlying timer implementation. Please refer to the
 //! documentation of the corresponding types for more details.
 
-use std::{
+extern crate alloc;
+
+use core::{
     ops::{Add, Sub},
     pin::Pin,
     task::{Context, Poll},
@@ -55,7 +91,9 @@ pub(crate) mod util;
 mod delay_queue;
 mod interval;
 mod sleep;
+mod stopwatch;
 mod timeout;
+mod timer_wheel;
 
 /// Copied from `tokio-util::time::delay_queue::wheel`
 mod wheel;
@@ -65,11 +103,48 @@ mod instant_impl;
 
 pub mod error;
 
+cfg_std! {
+    pub mod blocking;
+
+    mod coalesce;
+    mod delay_channel;
+    mod delay_map;
+    mod driver;
+    mod shared_delay_queue;
+    mod ttl_cache;
+    mod watchdog;
+
+    pub use coalesce::Coalescer;
+    pub use delay_channel::*;
+    pub use delay_map::*;
+    pub use driver::*;
+    pub use shared_delay_queue::*;
+    pub use ttl_cache::*;
+    pub use watchdog::*;
+}
+
+cfg_serde! {
+    mod snapshot;
+    pub use snapshot::*;
+}
+
+cfg_embassy_time! {
+    pub mod embassy;
+}
+
+cfg_not_wasm32! {
+    cfg_quanta! {
+        pub mod quanta;
+    }
+}
+
 // Re-exports
 pub use delay_queue::*;
 pub use interval::*;
 pub use sleep::*;
+pub use stopwatch::*;
 pub use timeout::*;
+pub use timer_wheel::*;
 
 /// A trait that defines a delay, which is the fundamental building block of this crate.
 /// 
@@ -84,6 +159,8 @@ pub use timeout::*;
 /// | [`futures_timer::Delay`] | `"futures-timer"` | non-wasm32 |
 /// | [`wasm_timer::Delay`] | `"wasm-timer"` | wasm32 |
 /// | [`fluvio_wasm_timer::Delay`] | `"fluvio-wasm-timer"` | wasm32 |
+/// | [`embassy::Timer`] | `"embassy-time"` | any |
+/// | [`quanta::Timer`] | `"quanta"` | non-wasm32 |
 /// 
 /// User could also provide their own implementations for other types to use the timer
 /// functionalities provided by this crate.
@@ -122,9 +199,29 @@ pub trait Delay {
 /// | [`tokio::time::Instant`] | `"tokio"` | non-wasm32 |
 /// | [`wasm_timer::Instant`] | `"wasm-timer"` | wasm32 |
 /// | [`fluvio_wasm_timer::Instant`] | `"fluvio-wasm-timer"` | wasm32 |
+/// | [`embassy::Instant`] | `"embassy-time"` | any |
+/// | [`quanta::Instant`](::quanta::Instant) | `"quanta"` | non-wasm32 |
+/// | [`quanta::CoarseInstant`] | `"quanta"` | non-wasm32 |
 /// 
 /// User could also provide their own implementations for other types to use the timer
 /// functionalities provided by this crate.
+/// 
+/// # Checked arithmetic
+/// 
+/// The timers compute their deadlines with [`checked_add`](Self::checked_add) and
+/// [`checked_sub`](Self::checked_sub), and saturate to [`far_future`](Self::far_future) instead
+/// of overflowing. The default implementations of these methods only know the `Add` and `Sub`
+/// implementations of the instant, so they:
+/// 
+/// - return `None` for any duration longer than about 30 years, even if the instant could
+///   represent the result, and
+/// - panic if the `Add` or `Sub` implementation panics, for example for an instant that is
+///   less than 30 years away from the end of its representable range.
+/// 
+/// The `std`, `tokio`, `quanta` and `embassy-time` instants override both methods with their
+/// native checked arithmetic. Implementations for other types should override them if their
+/// instants can get that close to the limits of their range, or to support durations longer
+/// than 30 years.
 pub trait Instant
 where
     Self: Add<Duration, Output = Self>
@@ -140,4 +237,74 @@ where
 {
     /// Returns the instant that is "now"
     fn now() -> Self;
+
+    /// Returns an instant far in the future, about 30 years from now.
+    ///
+    /// This is the deadline of a [`Sleep`] that never completes, and the instant that the timers
+    /// saturate to instead of overflowing.
+    fn far_future() -> Self {
+        Self::now() + FAR_FUTURE
+    }
+
+    /// Returns `self + duration`, or `None` if the result cannot be represented.
+    ///
+    /// The default implementation considers durations longer than the horizon of
+    /// [`far_future`], about 30 years, to be out of range, and relies on the `Add<Duration>`
+    /// implementation for shorter ones. Implementations that can tell exactly which instants
+    /// they can represent should override it.
+    ///
+    /// # Panics
+    ///
+    /// The default implementation panics if `self + duration` panics for a duration shorter
+    /// than about 30 years. See [the trait documentation](Self#checked-arithmetic).
+    ///
+    /// [`far_future`]: Self::far_future
+    fn checked_add(self, duration: Duration) -> Option<Self> {
+        if duration <= FAR_FUTURE {
+            Some(self + duration)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `self - duration`, or `None` if the result cannot be represented.
+    ///
+    /// Like [`checked_add`](Self::checked_add), the default implementation considers durations
+    /// longer than about 30 years to be out of range, and relies on the `Sub<Duration>`
+    /// implementation for shorter ones. Implementations whose subtraction can underflow within
+    /// that range, such as a clock that starts at boot, should override it.
+    ///
+    /// # Panics
+    ///
+    /// The default implementation panics if `self - duration` panics for a duration shorter
+    /// than about 30 years. See [the trait documentation](Self#checked-arithmetic).
+    fn checked_sub(self, duration: Duration) -> Option<Self> {
+        if duration <= FAR_FUTURE {
+            Some(self - duration)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the duration elapsed from `earlier` to `self`, or zero if `earlier` is later than
+    /// `self`.
+    ///
+    /// This saturates like `std::time::Instant::duration_since`. The default implementation is
+    /// [`saturating_duration_since`](Self::saturating_duration_since).
+    fn duration_since(self, earlier: Self) -> Duration {
+        self.saturating_duration_since(earlier)
+    }
+
+    /// Returns the duration elapsed from `earlier` to `self`, or zero if `earlier` is later than
+    /// `self`.
+    fn saturating_duration_since(self, earlier: Self) -> Duration {
+        if self > earlier {
+            self - earlier
+        } else {
+            Duration::ZERO
+        }
+    }
 }
+
+/// Roughly 30 years, which is what `tokio` uses as its "far future" instant.
+const FAR_FUTURE: Duration = Duration::from_secs(86400 * 365 * 30);
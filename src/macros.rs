@@ -62,3 +62,60 @@ macro_rules! cfg_futures_timer {
         )*
     };
 }
+
+macro_rules! cfg_embassy_time {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "embassy-time")]
+            $item
+        )*
+    };
+}
+
+macro_rules! cfg_quanta {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "quanta")]
+            $item
+        )*
+    };
+}
+
+macro_rules! cfg_serde {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "serde")]
+            $item
+        )*
+    };
+}
+
+macro_rules! cfg_std {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "std")]
+            $item
+        )*
+    };
+}
+
+/// Emits a `tracing` event at the TRACE level if the `"tracing"` feature is enabled.
+///
+/// The arguments are not evaluated otherwise, so values that are only computed for the event
+/// should be computed within the arguments.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)*);
+    };
+}
+
+/// Emits a `tracing` event at the DEBUG level if the `"tracing"` feature is enabled.
+///
+/// See [`trace!`] for how the arguments are evaluated.
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
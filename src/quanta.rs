@@ -0,0 +1,214 @@
+//! Fast clocks backed by `quanta`, and a [`Delay`] adapter that uses them.
+//!
+//! Reading the clock is on the hot path of the timers: [`Interval::poll_tick`] and
+//! [`DelayQueue::insert`] both read it. The [`Instant`] implementation for
+//! [`::quanta::Instant`] reads the Time Stamp Counter of the CPU where available, instead of
+//! making a system call. [`CoarseInstant`] is cheaper still: it reads a global instant that an
+//! upkeep thread, started with [`start_upkeep`], refreshes at a fixed interval.
+//!
+//! A `quanta` clock has no timer of its own, so [`Timer`] arms any other [`Delay`]
+//! implementation for the actual wakeup, converting deadlines from one clock to the other.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use timer_kit::{quanta, Interval};
+//!
+//! # #[cfg(feature = "smol")]
+//! # async fn example() {
+//! // The upkeep thread stops when the handle is dropped
+//! let _upkeep = quanta::start_upkeep(Duration::from_millis(1)).unwrap();
+//!
+//! let mut interval = Interval::<quanta::CoarseTimer<smol::Timer>>::new(Duration::from_millis(100));
+//! interval.tick().await;
+//! # }
+//! ```
+//!
+//! [`Interval::poll_tick`]: crate::Interval::poll_tick
+//! [`DelayQueue::insert`]: crate::DelayQueue::insert
+
+use std::{
+    ops::{Add, Sub},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::ready;
+use pin_project_lite::pin_project;
+
+use crate::{util::saturating_add, Delay, Instant};
+
+pub use ::quanta::{Error as UpkeepError, Handle as UpkeepHandle};
+
+/// Starts the upkeep thread that refreshes the time read by [`CoarseInstant::now`] every
+/// `interval`.
+///
+/// The thread stops when the returned handle is dropped. Until the thread is started,
+/// [`CoarseInstant::now`] reads the current time like [`::quanta::Instant::now`].
+///
+/// # Errors
+///
+/// Returns an error if an upkeep thread is already running in the process, or if the thread
+/// cannot be spawned.
+pub fn start_upkeep(interval: Duration) -> Result<UpkeepHandle, UpkeepError> {
+    ::quanta::Upkeep::new(interval).start()
+}
+
+/// A coarse instant that is only as precise as the interval of the upkeep thread.
+///
+/// Reading a `CoarseInstant` is a single atomic load of the time last stored by the upkeep thread
+/// started with [`start_upkeep`], so it lags behind the actual time by up to the interval of that
+/// thread.
+///
+/// [`CoarseInstant::now`]: crate::Instant::now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CoarseInstant(::quanta::Instant);
+
+impl CoarseInstant {
+    /// Returns the underlying `quanta::Instant`.
+    pub fn into_inner(self) -> ::quanta::Instant {
+        self.0
+    }
+}
+
+impl From<::quanta::Instant> for CoarseInstant {
+    fn from(instant: ::quanta::Instant) -> Self {
+        Self(instant)
+    }
+}
+
+impl From<CoarseInstant> for ::quanta::Instant {
+    fn from(instant: CoarseInstant) -> Self {
+        instant.0
+    }
+}
+
+impl Instant for CoarseInstant {
+    fn now() -> Self {
+        Self(::quanta::Instant::recent())
+    }
+
+    fn checked_add(self, duration: Duration) -> Option<Self> {
+        self.0.checked_add(duration).map(Self)
+    }
+
+    fn checked_sub(self, duration: Duration) -> Option<Self> {
+        self.0.checked_sub(duration).map(Self)
+    }
+
+    fn duration_since(self, earlier: Self) -> Duration {
+        self.0.duration_since(earlier.0)
+    }
+
+    fn saturating_duration_since(self, earlier: Self) -> Duration {
+        self.0.saturating_duration_since(earlier.0)
+    }
+}
+
+impl Add<Duration> for CoarseInstant {
+    type Output = Self;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        Self(self.0 + rhs)
+    }
+}
+
+impl Sub<Duration> for CoarseInstant {
+    type Output = Self;
+
+    fn sub(self, rhs: Duration) -> Self::Output {
+        Self(self.0 - rhs)
+    }
+}
+
+impl Sub<CoarseInstant> for CoarseInstant {
+    type Output = Duration;
+
+    fn sub(self, rhs: CoarseInstant) -> Self::Output {
+        self.0 - rhs.0
+    }
+}
+
+pin_project! {
+    /// A [`Delay`] whose deadlines are instants of the clock `I`, and that arms a delay of type `D`
+    /// for the actual wakeup.
+    ///
+    /// Deadlines are converted to the clock of `D` as the time remaining until them when the timer
+    /// is created or reset. When `D` completes before `I` has reached the deadline, the remaining
+    /// time is armed again, so the timer never completes early as measured by either clock, but may
+    /// complete late by up to the precision of `I`, which is the interval of the upkeep thread for a
+    /// [`CoarseInstant`].
+    #[derive(Debug)]
+    pub struct Timer<D, I = ::quanta::Instant> {
+        #[pin]
+        delay: D,
+        deadline: I,
+    }
+}
+
+/// A [`Timer`] whose deadlines are [`CoarseInstant`]s.
+pub type CoarseTimer<D> = Timer<D, CoarseInstant>;
+
+impl<D, I> Timer<D, I> {
+    /// Returns a reference to the underlying delay.
+    pub fn get_ref(&self) -> &D {
+        &self.delay
+    }
+
+    /// Consumes `self` and returns the underlying delay.
+    pub fn into_inner(self) -> D {
+        self.delay
+    }
+}
+
+impl<D, I> Delay for Timer<D, I>
+where
+    D: Delay,
+    I: Instant,
+{
+    type Value = D::Value;
+    type Instant = I;
+
+    fn delay(duration: Duration) -> Self {
+        Self {
+            delay: D::delay(duration),
+            deadline: saturating_add(I::now(), duration),
+        }
+    }
+
+    fn delay_until(deadline: I) -> Self {
+        Self {
+            delay: D::delay(deadline.saturating_duration_since(I::now())),
+            deadline,
+        }
+    }
+
+    fn deadline(&self) -> Option<I> {
+        Some(self.deadline)
+    }
+
+    fn poll_elapsed(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Value> {
+        let mut this = self.project();
+        loop {
+            let value = ready!(this.delay.as_mut().poll_elapsed(cx));
+
+            // The clock `I` may lag behind the clock of `D`, so wait until it reaches the deadline too
+            let remaining = this.deadline.saturating_duration_since(I::now());
+            if remaining.is_zero() {
+                return Poll::Ready(value);
+            }
+            this.delay
+                .as_mut()
+                .reset(saturating_add(D::Instant::now(), remaining));
+        }
+    }
+
+    fn reset(self: Pin<&mut Self>, deadline: I) {
+        let this = self.project();
+        let remaining = deadline.saturating_duration_since(I::now());
+        *this.deadline = deadline;
+        this.delay.reset(saturating_add(D::Instant::now(), remaining));
+    }
+}
@@ -0,0 +1,372 @@
+//! A [`DelayQueue`] that can be inserted into from other tasks and threads.
+//!
+//! See [`SharedDelayQueue`] for more details.
+
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::task::{self, Poll, Waker};
+
+use crate::error::DelayQueueError;
+use crate::util::saturating_add;
+use crate::{Delay, DelayQueue, Duration, Expired, Instant, Key};
+
+/// A [`DelayQueue`] with a single consumer and any number of [`Inserter`]
+/// handles.
+///
+/// The `SharedDelayQueue` is the consumer. It yields the expired values
+/// through [`poll_expired`] and its [`Stream`] implementation, like a
+/// [`DelayQueue`]. [`Inserter`]s are cloneable handles that insert, remove and
+/// reset values from any task or thread, and wake the consumer when a value
+/// must be yielded earlier than the consumer was waiting for.
+///
+/// The underlying timer is only ever created and reset by the consumer, so
+/// inserters can be used from threads that are not running the async runtime
+/// of the timer.
+///
+/// The stream ends once the queue is empty and all the inserters have been
+/// dropped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use futures::StreamExt;
+/// use timer_kit::SharedDelayQueue;
+///
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
+/// let mut queue = SharedDelayQueue::<smol::Timer, _>::new();
+/// let inserter = queue.inserter();
+///
+/// std::thread::spawn(move || {
+///     inserter.insert("foo", Duration::from_millis(100)).unwrap();
+/// });
+///
+/// assert_eq!(queue.next().await.unwrap().into_inner(), "foo");
+/// assert!(queue.next().await.is_none());
+/// # }
+/// ```
+///
+/// [`poll_expired`]: method@Self::poll_expired
+/// [`Stream`]: futures_util::Stream
+pub struct SharedDelayQueue<D: Delay, T> {
+    shared: Arc<Mutex<State<D, T>>>,
+}
+
+/// A cloneable handle to insert values into a [`SharedDelayQueue`].
+///
+/// This is created by [`SharedDelayQueue::inserter`].
+pub struct Inserter<D: Delay, T> {
+    shared: Arc<Mutex<State<D, T>>>,
+}
+
+/// A handle that removes values from a [`SharedDelayQueue`] without keeping
+/// the queue alive or open.
+pub(crate) struct Remover<D: Delay, T> {
+    shared: Weak<Mutex<State<D, T>>>,
+}
+
+impl<D, T> Remover<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    /// Removes the value associated with `key` from the queue, returning it
+    /// if it was still in the queue.
+    pub(crate) fn remove(&self, key: &Key) -> Option<Expired<T, D::Instant>> {
+        let shared = self.shared.upgrade()?;
+        let mut state = lock(&shared);
+        state.queue.remove_without_delay(key)
+    }
+}
+
+impl<D, T> Clone for Remover<D, T>
+where
+    D: Delay,
+{
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+struct State<D: Delay, T> {
+    queue: DelayQueue<D, T>,
+
+    /// Earliest deadline inserted by an inserter that the delay of the queue
+    /// has not been moved to yet
+    pending: Option<u64>,
+
+    /// Waker of the consumer
+    waker: Option<Waker>,
+
+    /// Number of live inserters
+    inserters: usize,
+
+    /// Whether the consumer has been dropped
+    closed: bool,
+}
+
+impl<D, T> State<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    /// Makes the consumer move its delay to `when` if it is earlier than the
+    /// delay it is waiting for.
+    fn schedule(&mut self, when: u64) {
+        if !self.queue.needs_delay_before(when) || self.pending.is_some_and(|p| p <= when) {
+            return;
+        }
+
+        self.pending = Some(when);
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+fn lock<D: Delay, T>(shared: &Mutex<State<D, T>>) -> MutexGuard<'_, State<D, T>> {
+    // The state is consistent even if an inserter panicked while holding the lock
+    shared.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+impl<D, T> SharedDelayQueue<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    /// Creates a new, empty, `SharedDelayQueue`.
+    pub fn new() -> Self {
+        Self::from_queue(DelayQueue::new())
+    }
+
+    /// Creates a `SharedDelayQueue` from an existing [`DelayQueue`], keeping
+    /// its values and configuration.
+    pub fn from_queue(queue: DelayQueue<D, T>) -> Self {
+        let state = State {
+            queue,
+            pending: None,
+            waker: None,
+            inserters: 0,
+            closed: false,
+        };
+
+        Self {
+            shared: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Creates a new [`Inserter`] for the queue.
+    pub fn inserter(&self) -> Inserter<D, T> {
+        lock(&self.shared).inserters += 1;
+
+        Inserter {
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Returns the number of values in the queue.
+    pub fn len(&self) -> usize {
+        lock(&self.shared).queue.len()
+    }
+
+    /// Returns `true` if there are no values in the queue.
+    pub fn is_empty(&self) -> bool {
+        lock(&self.shared).queue.is_empty()
+    }
+
+    /// Attempts to pull out the next expired value of the queue, registering
+    /// the current task for wakeup if the value is not yet available.
+    ///
+    /// Unlike [`DelayQueue::poll_expired`], this returns `Poll::Ready(None)`
+    /// only when the queue is empty and there are no inserters left.
+    pub fn poll_expired(&mut self, cx: &mut task::Context<'_>) -> Poll<Option<Expired<T, D::Instant>>> {
+        let mut state = lock(&self.shared);
+
+        if let Some(when) = state.pending.take() {
+            state.queue.set_delay_before(when);
+        }
+
+        match state.queue.poll_expired(cx) {
+            Poll::Ready(Some(expired)) => Poll::Ready(Some(expired)),
+            Poll::Ready(None) if state.inserters == 0 => Poll::Ready(None),
+            Poll::Ready(None) | Poll::Pending => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<D, T> Inserter<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    /// Inserts `value` into the queue set to expire after `timeout` elapses.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DelayQueueError::Closed`] if the consumer has been dropped,
+    /// or any of the errors of [`DelayQueue::try_insert`].
+    pub fn insert(&self, value: T, timeout: Duration) -> Result<Key, DelayQueueError> {
+        self.insert_at(value, saturating_add(D::Instant::now(), timeout))
+    }
+
+    /// Inserts `value` into the queue set to expire at `when`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DelayQueueError::Closed`] if the consumer has been dropped,
+    /// or any of the errors of [`DelayQueue::try_insert_at`].
+    pub fn insert_at(&self, value: T, when: D::Instant) -> Result<Key, DelayQueueError> {
+        self.insert_or_return(value, when).map_err(|(_, err)| err)
+    }
+
+    /// Inserts `value` into the queue set to expire at `when`, giving the
+    /// value back if it cannot be inserted.
+    pub(crate) fn insert_or_return(&self, value: T, when: D::Instant) -> Result<Key, (T, DelayQueueError)> {
+        let mut state = lock(&self.shared);
+        if state.closed {
+            return Err((value, DelayQueueError::Closed));
+        }
+
+        let (key, when) = state.queue.insert_without_delay(value, when, 0)?;
+        state.schedule(when);
+        Ok(key)
+    }
+
+    /// Creates a [`Remover`] for the queue.
+    pub(crate) fn remover(&self) -> Remover<D, T> {
+        Remover {
+            shared: Arc::downgrade(&self.shared),
+        }
+    }
+
+    /// Removes the value associated with `key` from the queue, returning it
+    /// if it was still in the queue.
+    pub fn remove(&self, key: &Key) -> Option<Expired<T, D::Instant>> {
+        lock(&self.shared).queue.remove_without_delay(key)
+    }
+
+    /// Resets the value associated with `key` to expire after `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DelayQueueError::Closed`] if the consumer has been dropped,
+    /// or any of the errors of [`DelayQueue::try_reset`].
+    pub fn reset(&self, key: &Key, timeout: Duration) -> Result<(), DelayQueueError> {
+        self.reset_at(key, saturating_add(D::Instant::now(), timeout))
+    }
+
+    /// Resets the value associated with `key` to expire at `when`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DelayQueueError::Closed`] if the consumer has been dropped,
+    /// or any of the errors of [`DelayQueue::try_reset_at`].
+    pub fn reset_at(&self, key: &Key, when: D::Instant) -> Result<(), DelayQueueError> {
+        let mut state = lock(&self.shared);
+        if state.closed {
+            return Err(DelayQueueError::Closed);
+        }
+
+        let when = state.queue.reset_without_delay(key, when)?;
+        state.schedule(when);
+        Ok(())
+    }
+
+    /// Returns `true` if the consumer has been dropped.
+    pub fn is_closed(&self) -> bool {
+        lock(&self.shared).closed
+    }
+}
+
+impl<D, T> Clone for Inserter<D, T>
+where
+    D: Delay,
+{
+    fn clone(&self) -> Self {
+        lock(&self.shared).inserters += 1;
+
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<D, T> Drop for Inserter<D, T>
+where
+    D: Delay,
+{
+    fn drop(&mut self) {
+        let mut state = lock(&self.shared);
+        state.inserters -= 1;
+
+        // Let the consumer end the stream if it is waiting on an empty queue
+        if state.inserters == 0 {
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<D, T> Drop for SharedDelayQueue<D, T>
+where
+    D: Delay,
+{
+    fn drop(&mut self) {
+        lock(&self.shared).closed = true;
+    }
+}
+
+impl<D, T> Default for SharedDelayQueue<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D, T> fmt::Debug for SharedDelayQueue<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = lock(&self.shared);
+        f.debug_struct("SharedDelayQueue")
+            .field("len", &state.queue.len())
+            .field("inserters", &state.inserters)
+            .finish()
+    }
+}
+
+impl<D, T> fmt::Debug for Inserter<D, T>
+where
+    D: Delay,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Inserter")
+            .field("closed", &lock(&self.shared).closed)
+            .finish()
+    }
+}
+
+impl<D, T> futures_util::Stream for SharedDelayQueue<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    type Item = Expired<T, D::Instant>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        SharedDelayQueue::poll_expired(self.get_mut(), cx)
+    }
+}
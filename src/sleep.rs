@@ -1,6 +1,7 @@
-use std::{pin::Pin, time::{Duration}, future::Future};
+use alloc::boxed::Box;
+use core::{pin::Pin, time::{Duration}, future::Future};
 
-use crate::{Delay, Instant};
+use crate::{util::saturating_add, Delay, Instant};
 
 /// Creates a new `Sleep` that completes after the specified duration.
 /// 
@@ -12,7 +13,10 @@ use crate::{Delay, Instant};
 /// use std::time::Duration;
 /// use timer_kit::sleep;
 /// 
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
 /// sleep::<smol::Timer>(Duration::from_millis(100)).await;
+/// # }
 /// ```
 /// 
 /// Creates a sleep with `fluvio_wasm_timer::Delay`
@@ -21,7 +25,10 @@ use crate::{Delay, Instant};
 /// use std::time::Duration;
 /// use timer_kit::sleep;
 /// 
+/// # #[cfg(feature = "fluvio-wasm-timer")]
+/// # async fn example() {
 /// sleep::<fluvio_wasm_timer::Delay>(Duration::from_millis(100)).await;
+/// # }
 /// ```
 pub fn sleep<D>(duration: Duration) -> Sleep<D> 
 where
@@ -40,17 +47,23 @@ where
 /// use std::time::{Duration, Instant};
 /// use timer_kit::sleep_until;
 /// 
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
 /// sleep_until::<smol::Timer>(Instant::now() + Duration::from_millis(100)).await;
+/// # }
 /// ```
 /// 
 /// Creates a sleep with `fluvio_wasm_timer::Delay`
 /// 
 /// ```rust,no_run
 /// use std::time::{Duration};
-/// use fluent_wasm_timer::Instant;
 /// use timer_kit::sleep_until;
 /// 
+/// # #[cfg(feature = "fluvio-wasm-timer")]
+/// # async fn example() {
+/// use fluvio_wasm_timer::Instant;
 /// sleep_until::<fluvio_wasm_timer::Delay>(Instant::now() + Duration::from_millis(100)).await;
+/// # }
 /// ```
 pub fn sleep_until<D>(deadline: D::Instant) -> Sleep<D> 
 where
@@ -68,8 +81,24 @@ where
 /// - `D`: The underlying timer type that implements the [`Delay`] trait
 #[derive(Debug)]
 pub struct Sleep<D: Delay> {
-    delay: Pin<Box<D>>,
+    timer: Timer<D>,
     deadline: D::Instant,
+    created: D::Instant,
+    elapsed: bool,
+    slack: Duration,
+}
+
+#[derive(Debug)]
+enum Timer<D: Delay> {
+    Delay {
+        delay: Pin<Box<D>>,
+
+        /// Instant the delay is currently armed at
+        armed: D::Instant,
+    },
+
+    #[cfg(feature = "std")]
+    Coalesced(crate::coalesce::Entry<D>),
 }
 
 impl<D> Sleep<D>
@@ -86,8 +115,11 @@ where
     /// use std::time::Duration;
     /// use timer_kit::Sleep;
     /// 
+    /// # #[cfg(feature = "smol")]
+    /// # async fn example() {
     /// let sleep = Sleep::<smol::Timer>::new(Duration::from_millis(100));
     /// sleep.await;
+    /// # }
     /// ```
     /// 
     /// Creates a sleep with `fluvio_wasm_timer::Delay`
@@ -96,16 +128,24 @@ where
     /// use std::time::Duration;
     /// use timer_kit::Sleep;
     /// 
+    /// # #[cfg(feature = "fluvio-wasm-timer")]
+    /// # async fn example() {
     /// let sleep = Sleep::<fluvio_wasm_timer::Delay>::new(Duration::from_millis(100));
     /// sleep.await;
+    /// # }
     /// ```
     pub fn new(duration: Duration) -> Self 
     {
+        let created = D::Instant::now();
         let delay = Box::pin(D::delay(duration));
-        let deadline = delay.deadline().unwrap_or(D::Instant::now() + duration);
+        let deadline = delay.deadline().unwrap_or(saturating_add(created, duration));
+        trace!(duration = ?duration, "sleep created");
         Self {
-            delay,
+            timer: Timer::Delay { delay, armed: deadline },
             deadline,
+            created,
+            elapsed: false,
+            slack: Duration::ZERO,
         }
     }
 
@@ -119,37 +159,158 @@ where
     /// use std::time::{Duration, Instant};
     /// use timer_kit::Sleep;
     /// 
+    /// # #[cfg(feature = "smol")]
+    /// # async fn example() {
     /// let sleep = Sleep::<smol::Timer>::new_until(Instant::now() + Duration::from_millis(100));
     /// sleep.await;
+    /// # }
     /// ```
     /// 
     /// Creates a sleep with `fluvio_wasm_timer::Delay`
     /// 
     /// ```rust,no_run
     /// use std::time::{Duration};
-    /// use fluent_wasm_timer::Instant;
     /// use timer_kit::Sleep;
     /// 
+    /// # #[cfg(feature = "fluvio-wasm-timer")]
+    /// # async fn example() {
+    /// use fluvio_wasm_timer::Instant;
     /// let sleep = Sleep::<fluvio_wasm_timer::Delay>::new_until(Instant::now() + Duration::from_millis(100));
     /// sleep.await;
+    /// # }
     /// ```
     pub fn new_until(deadline: D::Instant) -> Self {
+        trace!(
+            duration = ?deadline.saturating_duration_since(D::Instant::now()),
+            "sleep created"
+        );
         Self {
-            delay: Box::pin(D::delay_until(deadline)),
+            timer: Timer::Delay {
+                delay: Box::pin(D::delay_until(deadline)),
+                armed: deadline,
+            },
             deadline,
+            created: D::Instant::now(),
+            elapsed: false,
+            slack: Duration::ZERO,
         }
     }
 
+    /// Creates a new `Sleep` that is driven by a [`Coalescer`](crate::Coalescer)
+    #[cfg(feature = "std")]
+    pub(crate) fn coalesced(entry: crate::coalesce::Entry<D>, deadline: D::Instant, slack: Duration) -> Self {
+        Self {
+            timer: Timer::Coalesced(entry),
+            deadline,
+            created: D::Instant::now(),
+            elapsed: false,
+            slack,
+        }
+    }
+
+    /// Creates a new `Sleep` that never completes unless it is reset.
+    ///
+    /// The underlying delay is armed at a deadline far in the future (roughly 30 years from now).
+    /// This is useful for state machines that arm and disarm a single `Sleep` in a `select!`
+    /// loop.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use timer_kit::Sleep;
+    ///
+    /// # #[cfg(feature = "smol")]
+    /// # async fn example() {
+    /// let mut sleep = Sleep::<smol::Timer>::never();
+    /// assert!(!sleep.is_elapsed());
+    ///
+    /// // Arm the sleep
+    /// sleep.reset_after(Duration::from_millis(100));
+    /// (&mut sleep).await;
+    /// # }
+    /// ```
+    pub fn never() -> Self {
+        Self::new_until(D::Instant::far_future())
+    }
+
     /// Reset the `Sleep` to a new deadline
+    ///
+    /// With a non-zero [slack](Self::set_slack), the underlying delay is not re-armed if it is
+    /// already armed within the slack after the new deadline.
     pub fn reset(&mut self, deadline: D::Instant) {
+        let elapsed = core::mem::replace(&mut self.elapsed, false);
         self.deadline = deadline;
-        self.delay.as_mut().reset(deadline);
+        trace!(
+            remaining = ?deadline.saturating_duration_since(D::Instant::now()),
+            "sleep reset"
+        );
+
+        match &mut self.timer {
+            Timer::Delay { delay, armed } => {
+                let within_slack = *armed >= deadline && *armed <= saturating_add(deadline, self.slack);
+                if self.slack > Duration::ZERO && within_slack && !elapsed {
+                    return;
+                }
+
+                *armed = deadline;
+                delay.as_mut().reset(deadline);
+            }
+            #[cfg(feature = "std")]
+            Timer::Coalesced(entry) => entry.reset(deadline, self.slack),
+        }
+    }
+
+    /// Reset the `Sleep` to complete after the specified duration from now
+    pub fn reset_after(&mut self, duration: Duration) {
+        self.reset(saturating_add(D::Instant::now(), duration));
     }
 
     /// Gets the deadline
     pub fn deadline(&self) -> D::Instant {
         self.deadline
     }
+
+    /// Returns the slack of the `Sleep`.
+    pub fn slack(&self) -> Duration {
+        self.slack
+    }
+
+    /// Sets the slack of the `Sleep`, which is how long after its deadline the `Sleep` may
+    /// complete.
+    ///
+    /// A `Sleep` created by a [`Coalescer`](crate::Coalescer) completes together with the other
+    /// timers whose slack windows overlap with its own. Otherwise, a reset to a deadline that is
+    /// at most `slack` before the instant the underlying delay is armed at does not re-arm the
+    /// delay. The slack takes effect at the next reset or poll.
+    pub fn set_slack(&mut self, slack: Duration) {
+        self.slack = slack;
+    }
+
+    /// Returns `true` if the deadline has been reached.
+    ///
+    /// This is `true` once the `Sleep` has been polled to completion or the current instant is
+    /// at or after the deadline, and becomes `false` again after a reset to a later deadline.
+    pub fn is_elapsed(&self) -> bool {
+        self.elapsed || D::Instant::now() >= self.deadline
+    }
+
+    /// Returns the time remaining until the deadline, or zero if the deadline has been reached.
+    pub fn remaining(&self) -> Duration {
+        let now = D::Instant::now();
+        if self.deadline > now {
+            self.deadline - now
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    /// Returns the time elapsed since the `Sleep` was created.
+    ///
+    /// Resetting the `Sleep` does not change its creation instant.
+    pub fn elapsed_since_creation(&self) -> Duration {
+        D::Instant::now().saturating_duration_since(self.created)
+    }
 }
 
 impl<D> Future for Sleep<D>
@@ -159,7 +320,18 @@ where
 {
     type Output = D::Value;
 
-    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Self::Output> {
-        self.get_mut().delay.as_mut().poll_elapsed(cx)
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> core::task::Poll<Self::Output> {
+        let this = self.get_mut();
+        let value = match &mut this.timer {
+            Timer::Delay { delay, .. } => core::task::ready!(delay.as_mut().poll_elapsed(cx)),
+            #[cfg(feature = "std")]
+            Timer::Coalesced(entry) => core::task::ready!(entry.poll(this.deadline, this.slack, cx)),
+        };
+        this.elapsed = true;
+        trace!(
+            lateness = ?D::Instant::now().saturating_duration_since(this.deadline),
+            "sleep elapsed"
+        );
+        core::task::Poll::Ready(value)
     }
 }
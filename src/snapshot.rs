@@ -0,0 +1,216 @@
+//! Portable snapshots of the contents of a [`DelayQueue`].
+//!
+//! See [`Snapshot`] for more details.
+
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::DelayQueueError;
+use crate::util::saturating_add;
+use crate::{Delay, DelayQueue, Duration, Instant};
+
+/// A portable copy of the values of a [`DelayQueue`] and their deadlines.
+///
+/// A snapshot is taken with [`DelayQueue::snapshot`] and can be serialized
+/// with any `serde` format. As the instants of a [`Delay`] are only
+/// meaningful within the process that created them, every entry records both
+/// the time that was remaining when the snapshot was taken and its deadline as
+/// a wall-clock [`SystemTime`].
+///
+/// The snapshot is loaded back with [`DelayQueue::restore`], where a
+/// [`RestorePolicy`] decides what happens to the entries whose deadline has
+/// passed in the meantime.
+///
+/// Taking and restoring snapshots reads the wall clock, which is not available
+/// on `wasm32-unknown-unknown`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use timer_kit::{DelayQueue, RestorePolicy, Snapshot};
+///
+/// # #[cfg(feature = "smol")]
+/// # fn example() {
+/// let mut queue = DelayQueue::<smol::Timer, String>::new();
+/// queue.insert("foo".to_string(), Duration::from_secs(60));
+///
+/// let json = serde_json::to_string(&queue.snapshot()).unwrap();
+///
+/// // ... after a restart
+/// let snapshot: Snapshot<String> = serde_json::from_str(&json).unwrap();
+/// let mut queue = DelayQueue::<smol::Timer, String>::new();
+/// queue.restore(snapshot, RestorePolicy::FireImmediately).unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot<T> {
+    taken_at: SystemTime,
+    entries: Vec<SnapshotEntry<T>>,
+}
+
+/// A value of a [`Snapshot`] along with its deadline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotEntry<T> {
+    /// The value stored in the queue.
+    pub value: T,
+
+    /// The time that was remaining until the deadline when the snapshot was
+    /// taken.
+    pub remaining: Duration,
+
+    /// The wall-clock deadline of the value.
+    pub deadline: SystemTime,
+
+    /// The priority the value was inserted with.
+    #[serde(default)]
+    pub priority: u32,
+}
+
+/// How [`DelayQueue::restore`] handles the entries of a [`Snapshot`] whose
+/// wall-clock deadline has already passed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RestorePolicy {
+    /// Entries whose deadline has passed expire as soon as the queue is
+    /// polled. Other entries keep their wall-clock deadline.
+    #[default]
+    FireImmediately,
+
+    /// Entries whose deadline has passed are discarded. Other entries keep
+    /// their wall-clock deadline.
+    Drop,
+
+    /// Every entry is restored with the time that was remaining when the
+    /// snapshot was taken, as if no time had passed in between.
+    Shift,
+}
+
+impl<T> Snapshot<T> {
+    /// Returns the wall-clock time at which the snapshot was taken.
+    pub fn taken_at(&self) -> SystemTime {
+        self.taken_at
+    }
+
+    /// Returns the entries of the snapshot, sorted by deadline.
+    pub fn entries(&self) -> &[SnapshotEntry<T>] {
+        &self.entries
+    }
+
+    /// Consumes the snapshot, returning its entries sorted by deadline.
+    pub fn into_entries(self) -> Vec<SnapshotEntry<T>> {
+        self.entries
+    }
+
+    /// Returns the number of entries in the snapshot.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the snapshot has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<D, T> DelayQueue<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    /// Takes a [`Snapshot`] of the values in the queue and their deadlines.
+    ///
+    /// The entries are sorted by deadline, and values with the same deadline
+    /// are kept in the order a queue with
+    /// [`ExpirationOrder::Fifo`](crate::ExpirationOrder::Fifo) would yield
+    /// them. Values that have expired but have not been pulled out of the
+    /// queue yet are included with no time remaining.
+    pub fn snapshot(&self) -> Snapshot<T>
+    where
+        T: Clone,
+    {
+        let taken_at = SystemTime::now();
+        let now = D::Instant::now();
+
+        let entries = self
+            .sorted_entries()
+            .into_iter()
+            .map(|(value, deadline, priority)| {
+                let remaining = if deadline > now {
+                    deadline - now
+                } else {
+                    Duration::ZERO
+                };
+
+                SnapshotEntry {
+                    value: value.clone(),
+                    remaining,
+                    deadline: taken_at + remaining,
+                    priority,
+                }
+            })
+            .collect();
+
+        Snapshot { taken_at, entries }
+    }
+
+    /// Inserts the entries of `snapshot` into the queue, applying `policy` to
+    /// the entries whose deadline has already passed.
+    ///
+    /// The deadlines are computed from the wall-clock deadline of every entry,
+    /// or from the remaining time with [`RestorePolicy::Shift`]. An entry is
+    /// never given more time than it had remaining when the snapshot was
+    /// taken, even if the wall clock has been moved backwards. The underlying
+    /// timer is only updated once.
+    ///
+    /// Returns the number of entries that were inserted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DelayQueueError::CapacityExceeded`] without restoring any
+    /// entry if the queue cannot hold all the entries of the snapshot.
+    ///
+    /// Otherwise, returns any of the errors of [`try_insert_at`] for the first
+    /// entry that cannot be inserted. The entries restored before it stay in
+    /// the queue, and the remaining entries are dropped.
+    ///
+    /// [`try_insert_at`]: method@Self::try_insert_at
+    pub fn restore(&mut self, snapshot: Snapshot<T>, policy: RestorePolicy) -> Result<usize, DelayQueueError> {
+        let wall_now = SystemTime::now();
+        let now = D::Instant::now();
+        self.try_reserve(snapshot.len())?;
+
+        let mut restored = 0;
+        let mut earliest = None;
+        let mut result = Ok(());
+        for entry in snapshot.entries {
+            let remaining = match policy {
+                RestorePolicy::Shift => entry.remaining,
+                RestorePolicy::FireImmediately | RestorePolicy::Drop => {
+                    match entry.deadline.duration_since(wall_now) {
+                        Ok(remaining) => remaining.min(entry.remaining),
+                        Err(_) if policy == RestorePolicy::Drop => continue,
+                        Err(_) => Duration::ZERO,
+                    }
+                }
+            };
+
+            match self.insert_without_delay(entry.value, saturating_add(now, remaining), entry.priority) {
+                Ok((_, when)) => {
+                    restored += 1;
+                    earliest = Some(earliest.map_or(when, |e: u64| e.min(when)));
+                }
+                Err((_, err)) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+
+        if let Some(when) = earliest {
+            self.schedule_delay(when);
+        }
+
+        result.map(|()| restored)
+    }
+}
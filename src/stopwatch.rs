@@ -0,0 +1,351 @@
+//! Measurement of elapsed time with the clock of the timers.
+//!
+//! See [`Stopwatch`], [`measure()`] and [`ElapsedHistogram`] for more details.
+
+use alloc::vec::Vec;
+use core::{future::Future, pin::Pin, task::Poll, time::Duration};
+
+use pin_project_lite::pin_project;
+
+use crate::Instant;
+
+/// Measures the running time of a future with the clock `I`.
+///
+/// The returned future yields the output of `fut` along with the time elapsed between the first
+/// poll and the completion of `fut`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use timer_kit::measure;
+///
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
+/// let (_, elapsed) = measure::<std::time::Instant, _>(smol::Timer::after(Duration::from_millis(100))).await;
+/// assert!(elapsed >= Duration::from_millis(100));
+/// # }
+/// ```
+pub fn measure<I, Fut>(fut: Fut) -> Measure<I, Fut>
+where
+    I: Instant,
+    Fut: Future,
+{
+    Measure { start: None, fut }
+}
+
+pin_project! {
+    /// A future that measures the running time of another future.
+    ///
+    /// This is created by [`measure()`].
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Measure<I, Fut> {
+        start: Option<I>,
+
+        #[pin]
+        fut: Fut,
+    }
+}
+
+impl<I, Fut> Future for Measure<I, Fut>
+where
+    I: Instant,
+    Fut: Future,
+{
+    type Output = (Fut::Output, Duration);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let start = *this.start.get_or_insert_with(I::now);
+
+        match this.fut.poll(cx) {
+            Poll::Ready(output) => Poll::Ready((output, I::now().saturating_duration_since(start))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A stopwatch that measures running time with the clock `I`.
+///
+/// The stopwatch only accumulates time while it is running, between [`start`](Self::start) and
+/// [`stop`](Self::stop). The running time can also be split into laps with [`lap`](Self::lap).
+///
+/// Using the [`Instant`] of the timers, rather than `std::time::Instant`, keeps the measurements
+/// consistent with the timers, for example when they are driven by a mock clock in tests.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Instant;
+/// use timer_kit::Stopwatch;
+///
+/// # #[cfg(feature = "std")]
+/// # fn main() {
+/// let mut stopwatch = Stopwatch::<Instant>::start_new();
+/// // ... first step
+/// let first = stopwatch.lap();
+/// // ... second step
+/// let second = stopwatch.lap();
+///
+/// let total = stopwatch.stop();
+/// assert!(total >= first + second);
+/// # }
+/// # #[cfg(not(feature = "std"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Stopwatch<I> {
+    /// Instant at which the stopwatch was last started, if it is running
+    started: Option<I>,
+
+    /// Running time accumulated before the stopwatch was last started
+    elapsed: Duration,
+
+    /// Running time at the end of the last lap
+    lap: Duration,
+}
+
+impl<I: Instant> Stopwatch<I> {
+    /// Creates a new `Stopwatch` that is stopped.
+    pub fn new() -> Self {
+        Self {
+            started: None,
+            elapsed: Duration::ZERO,
+            lap: Duration::ZERO,
+        }
+    }
+
+    /// Creates a new `Stopwatch` that is running.
+    pub fn start_new() -> Self {
+        let mut stopwatch = Self::new();
+        stopwatch.start();
+        stopwatch
+    }
+
+    /// Starts the stopwatch. This does nothing if the stopwatch is already running.
+    pub fn start(&mut self) {
+        if self.started.is_none() {
+            self.started = Some(I::now());
+        }
+    }
+
+    /// Stops the stopwatch, and returns the total running time.
+    pub fn stop(&mut self) -> Duration {
+        if let Some(started) = self.started.take() {
+            self.elapsed += I::now().saturating_duration_since(started);
+        }
+        self.elapsed
+    }
+
+    /// Stops the stopwatch, and sets its running time back to zero.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Sets the running time back to zero, and starts the stopwatch.
+    pub fn restart(&mut self) {
+        *self = Self::start_new();
+    }
+
+    /// Returns `true` if the stopwatch is running.
+    pub fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// Returns the total running time.
+    pub fn elapsed(&self) -> Duration {
+        match self.started {
+            Some(started) => self.elapsed + I::now().saturating_duration_since(started),
+            None => self.elapsed,
+        }
+    }
+
+    /// Returns the running time of the current lap, without starting a new one.
+    pub fn split(&self) -> Duration {
+        self.elapsed().saturating_sub(self.lap)
+    }
+
+    /// Ends the current lap and starts a new one, returning the running time of the lap.
+    pub fn lap(&mut self) -> Duration {
+        let elapsed = self.elapsed();
+        let lap = elapsed.saturating_sub(self.lap);
+        self.lap = elapsed;
+        lap
+    }
+}
+
+impl<I: Instant> Default for Stopwatch<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A histogram of elapsed durations.
+///
+/// Durations are counted in buckets delimited by increasing upper bounds, and the durations longer
+/// than the last bound are counted in an overflow bucket. The default bounds double from 1
+/// millisecond to about 33 seconds.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use timer_kit::ElapsedHistogram;
+///
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
+/// let mut histogram = ElapsedHistogram::new();
+/// for _ in 0..10 {
+///     histogram
+///         .measure::<std::time::Instant, _>(smol::Timer::after(Duration::from_millis(10)))
+///         .await;
+/// }
+///
+/// assert_eq!(histogram.count(), 10);
+/// println!("p99: {:?}", histogram.quantile(0.99));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElapsedHistogram {
+    /// Upper bounds of the buckets, in increasing order
+    bounds: Vec<Duration>,
+
+    /// Number of durations in each bucket, followed by the overflow bucket
+    counts: Vec<u64>,
+
+    count: u64,
+    sum: Duration,
+    min: Option<Duration>,
+    max: Option<Duration>,
+}
+
+impl ElapsedHistogram {
+    /// Creates a new, empty, `ElapsedHistogram` with the default bounds.
+    pub fn new() -> Self {
+        Self::with_bounds((0..16).map(|exp| Duration::from_millis(1 << exp)))
+    }
+
+    /// Creates a new, empty, `ElapsedHistogram` whose buckets have the upper bounds `bounds`.
+    ///
+    /// The bounds are sorted and deduplicated.
+    pub fn with_bounds<B>(bounds: B) -> Self
+    where
+        B: IntoIterator<Item = Duration>,
+    {
+        let mut bounds: Vec<_> = bounds.into_iter().collect();
+        bounds.sort_unstable();
+        bounds.dedup();
+
+        Self {
+            counts: alloc::vec![0; bounds.len() + 1],
+            bounds,
+            count: 0,
+            sum: Duration::ZERO,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Records an elapsed duration.
+    pub fn record(&mut self, elapsed: Duration) {
+        let bucket = self.bounds.partition_point(|bound| *bound < elapsed);
+        self.counts[bucket] += 1;
+
+        self.count += 1;
+        self.sum = self.sum.saturating_add(elapsed);
+        self.min = Some(self.min.map_or(elapsed, |min| min.min(elapsed)));
+        self.max = Some(self.max.map_or(elapsed, |max| max.max(elapsed)));
+    }
+
+    /// Measures the running time of `fut` with the clock `I`, and records it.
+    ///
+    /// Returns the output of `fut`.
+    pub async fn measure<I, Fut>(&mut self, fut: Fut) -> Fut::Output
+    where
+        I: Instant,
+        Fut: Future,
+    {
+        let (output, elapsed) = measure::<I, _>(fut).await;
+        self.record(elapsed);
+        output
+    }
+
+    /// Returns the number of recorded durations.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the sum of the recorded durations.
+    pub fn sum(&self) -> Duration {
+        self.sum
+    }
+
+    /// Returns the shortest recorded duration.
+    pub fn min(&self) -> Option<Duration> {
+        self.min
+    }
+
+    /// Returns the longest recorded duration.
+    pub fn max(&self) -> Option<Duration> {
+        self.max
+    }
+
+    /// Returns the mean of the recorded durations.
+    pub fn mean(&self) -> Option<Duration> {
+        let mean = self.sum.as_nanos().checked_div(u128::from(self.count))?;
+        Some(Duration::from_nanos(mean as u64))
+    }
+
+    /// Returns an upper estimate of the `quantile` of the recorded durations, which is the upper
+    /// bound of the bucket it falls in, or the longest recorded duration for the overflow bucket.
+    ///
+    /// Returns `None` if no duration has been recorded.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `quantile` is not within `[0, 1]`.
+    #[track_caller]
+    pub fn quantile(&self, quantile: f64) -> Option<Duration> {
+        assert!((0.0..=1.0).contains(&quantile), "quantile must be within [0, 1]");
+
+        let max = self.max?;
+
+        // Rank of the duration at the quantile, starting at 1. `f64::ceil` needs std, so round the
+        // product up by hand.
+        let product = quantile * self.count as f64;
+        let truncated = product as u64;
+        let rank = (truncated + u64::from(product > truncated as f64)).max(1);
+        let mut seen = 0;
+        let bucket = self.counts.iter().position(|count| {
+            seen += count;
+            seen >= rank
+        })?;
+        Some(self.bounds.get(bucket).map_or(max, |bound| max.min(*bound)))
+    }
+
+    /// Returns an iterator over the buckets of the histogram, as their upper bound and their number
+    /// of durations. The overflow bucket comes last, with an upper bound of `None`.
+    pub fn buckets(&self) -> impl Iterator<Item = (Option<Duration>, u64)> + '_ {
+        self.bounds
+            .iter()
+            .map(|bound| Some(*bound))
+            .chain(core::iter::once(None))
+            .zip(self.counts.iter().copied())
+    }
+
+    /// Removes every recorded duration, keeping the bounds.
+    pub fn clear(&mut self) {
+        self.counts.iter_mut().for_each(|count| *count = 0);
+        self.count = 0;
+        self.sum = Duration::ZERO;
+        self.min = None;
+        self.max = None;
+    }
+}
+
+impl Default for ElapsedHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
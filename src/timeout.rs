@@ -1,4 +1,4 @@
-use std::{future::Future, pin::Pin, task::Poll, time::Duration};
+use core::{future::Future, pin::Pin, task::Poll, time::Duration};
 
 use pin_project_lite::pin_project;
 
@@ -14,7 +14,10 @@ use crate::{error::Elapsed, Delay};
 /// use std::time::Duration;
 /// use timer_kit::timeout;
 /// 
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
 /// let result = timeout::<smol::Timer, _>(Duration::from_millis(100), async { }).await;
+/// # }
 /// ```
 /// 
 /// Creates a timeout with `fluvio_wasm_timer::Delay`
@@ -23,7 +26,10 @@ use crate::{error::Elapsed, Delay};
 /// use std::time::Duration;
 /// use timer_kit::timeout;
 /// 
+/// # #[cfg(feature = "fluvio-wasm-timer")]
+/// # async fn example() {
 /// let result = timeout::<fluvio_wasm_timer::Delay, _>(Duration::from_millis(100), async { }).await;
+/// # }
 /// ```
 pub fn timeout<D, Fut>(duration: Duration, fut: Fut) -> Timeout<D, Fut>
 where
@@ -43,17 +49,23 @@ where
 /// use std::time::{Duration, Instant};
 /// use timer_kit::timeout_at;
 /// 
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
 /// let result = timeout_at::<smol::Timer, _>(Instant::now() + Duration::from_millis(100), async { }).await;
+/// # }
 /// ```
 /// 
 /// Creates a timeout with `fluvio_wasm_timer::Delay`
 /// 
 /// ```rust,no_run
 /// use std::time::{Duration};
-/// use fluent_wasm_timer::Instant;
 /// use timer_kit::timeout_at;
 /// 
+/// # #[cfg(feature = "fluvio-wasm-timer")]
+/// # async fn example() {
+/// use fluvio_wasm_timer::Instant;
 /// let result = timeout_at::<fluvio_wasm_timer::Delay, _>(Instant::now() + Duration::from_millis(100), async { }).await;
+/// # }
 /// ```
 pub fn timeout_at<D, Fut>(deadline: D::Instant, fut: Fut) -> Timeout<D, Fut>
 where
@@ -80,6 +92,8 @@ pin_project! {
 
         #[pin]
         future: Fut,
+
+        label: Option<&'static str>,
     }
 }
 
@@ -98,7 +112,10 @@ where
     /// use std::time::Duration;
     /// use timer_kit::Timeout;
     /// 
+    /// # #[cfg(feature = "smol")]
+    /// # async fn example() {
     /// let result = Timeout::<smol::Timer, _>::new(Duration::from_millis(100), async {}).await;
+    /// # }
     /// ```
     /// 
     /// Creates a timeout with `fluvio_wasm_timer::Delay`
@@ -107,12 +124,16 @@ where
     /// use std::time::Duration;
     /// use timer_kit::Timeout;
     /// 
+    /// # #[cfg(feature = "fluvio-wasm-timer")]
+    /// # async fn example() {
     /// let result = Timeout::<fluvio_wasm_timer::Delay, _>::new(Duration::from_millis(100), async {}).await;
+    /// # }
     /// ```
     pub fn new(duration: Duration, future: Fut) -> Self {
         Self {
             delay: D::delay(duration),
             future,
+            label: None,
         }
     }
 
@@ -126,24 +147,56 @@ where
     /// use std::time::{Duration, Instant};
     /// use timer_kit::Timeout;
     ///
+    /// # #[cfg(feature = "smol")]
+    /// # async fn example() {
     /// let result = Timeout::<smol::Timer, _>::new_at(Instant::now() + Duration::from_millis(100), async {}).await;
+    /// # }
     /// ```
     ///
     /// Creates a timeout with `fluvio_wasm_timer::Delay`
     /// 
     /// ```rust,no_run
     /// use std::time::Duration;
-    /// use fluent_wasm_timer::Instant;
     /// use timer_kit::Timeout;
     /// 
+    /// # #[cfg(feature = "fluvio-wasm-timer")]
+    /// # async fn example() {
+    /// use fluvio_wasm_timer::Instant;
     /// let result = Timeout::<fluvio_wasm_timer::Delay, _>::new_at(Instant::now() + Duration::from_millis(100), async {}).await;
+    /// # }
     /// ```
     pub fn new_at(deadline: D::Instant, future: Fut) -> Self {
         Self {
             delay: D::delay_until(deadline),
             future,
+            label: None,
         }
     }
+
+    /// Sets a label that identifies the `Timeout` in the `tracing` event emitted when it elapses.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use timer_kit::timeout;
+    ///
+    /// # #[cfg(feature = "smol")]
+    /// # async fn example() {
+    /// let result = timeout::<smol::Timer, _>(Duration::from_millis(100), async {})
+    ///     .with_label("handshake")
+    ///     .await;
+    /// # }
+    /// ```
+    pub fn with_label(mut self, label: &'static str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Returns the label of the `Timeout`, if any.
+    pub fn label(&self) -> Option<&'static str> {
+        self.label
+    }
 }
 
 impl<D, Fut> Future for Timeout<D, Fut>
@@ -155,8 +208,8 @@ where
 
     fn poll(
         self: Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<Self::Output> {
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Self::Output> {
         let mut this = self.project();
 
         if let Poll::Ready(output) = this.future.poll(cx) {
@@ -164,7 +217,10 @@ where
         }
 
         match this.delay.as_mut().poll_elapsed(cx) {
-            Poll::Ready(_) => Poll::Ready(Err(Elapsed::new())),
+            Poll::Ready(_) => {
+                debug!(label = this.label.unwrap_or_default(), "timeout elapsed");
+                Poll::Ready(Err(Elapsed::new()))
+            }
             Poll::Pending => Poll::Pending,
         }
     }
@@ -0,0 +1,387 @@
+//! A hierarchical timing wheel that is driven by the caller.
+//!
+//! See [`TimerWheel`] for more details.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use slab::Slab;
+
+use crate::wheel::{self, Wheel};
+
+/// A hierarchical timing wheel that stores values until a deadline, expressed
+/// in ticks, is reached.
+///
+/// This is the timing wheel that backs [`DelayQueue`](crate::DelayQueue),
+/// without any [`Delay`](crate::Delay) backend. The caller decides what a tick
+/// is and drives the wheel by calling [`advance`] with the current tick, for
+/// example once per iteration of an event loop or a game loop, and uses
+/// [`next_expiration`] to know how long it may sleep.
+///
+/// Inserting, cancelling and expiring a value are constant time operations.
+/// The wheel covers 63 × 2^30 ticks, just under 2^36, ahead of the current
+/// tick, and values further in the future are kept aside, in logarithmic
+/// time, until they come within range. [`next_expiration`] scans the values
+/// of the earliest slot of the wheel, and the values that were already due
+/// when they were inserted, so it is linear in the number of these values.
+///
+/// # Example
+///
+/// ```rust
+/// use timer_kit::TimerWheel;
+///
+/// let mut wheel = TimerWheel::new();
+/// wheel.insert(10, "foo");
+/// let handle = wheel.insert(20, "bar");
+/// wheel.insert(30, "baz");
+///
+/// assert_eq!(wheel.cancel(handle), Some("bar"));
+/// assert_eq!(wheel.next_expiration(), Some(10));
+///
+/// let expired: Vec<_> = wheel.advance(25).collect();
+/// assert_eq!(expired, vec![(10, "foo")]);
+/// assert_eq!(wheel.elapsed(), 25);
+/// assert_eq!(wheel.next_expiration(), Some(30));
+/// ```
+///
+/// [`advance`]: method@Self::advance
+/// [`next_expiration`]: method@Self::next_expiration
+pub struct TimerWheel<T> {
+    wheel: Wheel<List<T>>,
+    store: Slab<Node<T>>,
+
+    /// Values that were inserted with a deadline that had already been reached
+    ready: List<T>,
+
+    /// Generation of the next inserted value
+    next_generation: u64,
+
+    /// Latest tick passed to `advance`, which the wheel may not have reached
+    /// yet if the iterator was not exhausted
+    now: u64,
+}
+
+/// A handle to a value inserted into a [`TimerWheel`].
+///
+/// A handle is only valid until its value expires or is cancelled. Handles
+/// are never reused, so a stale handle is not mistaken for the handle of a
+/// newer value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerHandle {
+    index: usize,
+    generation: u64,
+}
+
+/// An iterator over the values of a [`TimerWheel`] whose deadline has been
+/// reached.
+///
+/// This is created by [`TimerWheel::advance`]. Values that have not been
+/// yielded when the iterator is dropped are yielded by the next call to
+/// [`TimerWheel::advance`].
+pub struct Advance<'a, T> {
+    wheel: &'a mut TimerWheel<T>,
+    now: u64,
+}
+
+struct Node<T> {
+    value: T,
+    when: u64,
+    generation: u64,
+    ready: bool,
+    next: Option<usize>,
+    prev: Option<usize>,
+}
+
+/// A doubly linked list of the entries of a slot, stored in the slab
+struct List<T> {
+    head: Option<usize>,
+    tail: Option<usize>,
+    _p: PhantomData<fn() -> T>,
+}
+
+impl<T> TimerWheel<T> {
+    /// Creates a new, empty, `TimerWheel` whose current tick is zero.
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates a new, empty, `TimerWheel` with the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            wheel: Wheel::new(),
+            store: Slab::with_capacity(capacity),
+            ready: List::default(),
+            next_generation: 0,
+            now: 0,
+        }
+    }
+
+    /// Returns the current tick of the wheel, which is the latest tick passed
+    /// to [`advance`](Self::advance).
+    pub fn elapsed(&self) -> u64 {
+        self.now
+    }
+
+    /// Inserts `value` to expire at the tick `when`.
+    ///
+    /// A value whose deadline has already been reached is yielded by the next
+    /// call to [`advance`](Self::advance).
+    pub fn insert(&mut self, when: u64, value: T) -> TimerHandle {
+        let generation = self.next_generation;
+        self.next_generation = self.next_generation.wrapping_add(1);
+
+        let index = self.store.insert(Node {
+            value,
+            when,
+            generation,
+            ready: false,
+            next: None,
+            prev: None,
+        });
+
+        if let Err((index, wheel::InsertError::Elapsed)) = self.wheel.insert(when, index, &mut self.store) {
+            self.push_ready(index);
+        }
+
+        TimerHandle { index, generation }
+    }
+
+    /// Cancels the value of `handle`, returning it if it had not expired yet.
+    pub fn cancel(&mut self, handle: TimerHandle) -> Option<T> {
+        if !self.contains(handle) {
+            return None;
+        }
+
+        if self.store[handle.index].ready {
+            wheel::Stack::remove(&mut self.ready, &handle.index, &mut self.store);
+        } else {
+            self.wheel.remove(&handle.index, &mut self.store);
+        }
+        Some(self.store.remove(handle.index).value)
+    }
+
+    /// Returns `true` if the value of `handle` has not expired or been
+    /// cancelled yet.
+    pub fn contains(&self, handle: TimerHandle) -> bool {
+        self.store
+            .get(handle.index)
+            .is_some_and(|node| node.generation == handle.generation)
+    }
+
+    /// Returns a reference to the value of `handle`.
+    pub fn get(&self, handle: TimerHandle) -> Option<&T> {
+        self.store
+            .get(handle.index)
+            .filter(|node| node.generation == handle.generation)
+            .map(|node| &node.value)
+    }
+
+    /// Returns a mutable reference to the value of `handle`.
+    pub fn get_mut(&mut self, handle: TimerHandle) -> Option<&mut T> {
+        self.store
+            .get_mut(handle.index)
+            .filter(|node| node.generation == handle.generation)
+            .map(|node| &mut node.value)
+    }
+
+    /// Returns the tick at which the value of `handle` expires.
+    pub fn deadline(&self, handle: TimerHandle) -> Option<u64> {
+        self.store
+            .get(handle.index)
+            .filter(|node| node.generation == handle.generation)
+            .map(|node| node.when)
+    }
+
+    /// Advances the wheel to the tick `now`, returning an iterator over the
+    /// values whose deadline has been reached along with their deadline.
+    ///
+    /// Values that were already due when they were inserted are yielded
+    /// first, in insertion order, followed by the other values by increasing
+    /// deadline. The wheel never goes back in time, so a `now` that is
+    /// earlier than the current tick only yields the values that are already
+    /// due.
+    pub fn advance(&mut self, now: u64) -> Advance<'_, T> {
+        self.now = self.now.max(now);
+        let now = self.now;
+        Advance { wheel: self, now }
+    }
+
+    /// Returns the earliest deadline of the values in the wheel.
+    ///
+    /// This may be earlier than the current tick if some values are already
+    /// due. This walks the values that are already due and the values in the
+    /// earliest slot of the wheel.
+    pub fn next_expiration(&self) -> Option<u64> {
+        let ready = wheel::Stack::min_when(&self.ready, &self.store);
+        match (ready, self.wheel.peek_min_when(&self.store)) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Returns the number of values in the wheel.
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    /// Returns `true` if there are no values in the wheel.
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    /// Removes all values from the wheel, keeping its current tick.
+    pub fn clear(&mut self) {
+        self.wheel = Wheel::new();
+        self.ready = List::default();
+        self.store.clear();
+
+        // Polling an empty wheel moves it to the current tick
+        self.wheel.poll(self.now, &mut self.store);
+    }
+
+    fn push_ready(&mut self, index: usize) {
+        self.store[index].ready = true;
+        wheel::Stack::push(&mut self.ready, index, &mut self.store);
+    }
+
+    fn pop_expired(&mut self, now: u64) -> Option<(u64, T)> {
+        let index = match wheel::Stack::pop(&mut self.ready, &mut self.store) {
+            Some(index) => index,
+            None => loop {
+                // Step through the instants the wheel must be polled at so
+                // that far deadlines are moved into range before they are due.
+                match self.wheel.poll_at() {
+                    Some(at) if at <= now => {
+                        if let Some(index) = self.wheel.poll(at, &mut self.store) {
+                            break index;
+                        }
+                    }
+                    _ => {
+                        self.wheel.poll(now, &mut self.store);
+                        return None;
+                    }
+                }
+            },
+        };
+
+        let node = self.store.remove(index);
+        Some((node.when, node.value))
+    }
+}
+
+impl<T> Iterator for Advance<'_, T> {
+    type Item = (u64, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.wheel.pop_expired(self.now)
+    }
+}
+
+impl<T> wheel::Stack for List<T> {
+    type Owned = usize;
+    type Borrowed = usize;
+    type Store = Slab<Node<T>>;
+
+    fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    fn push(&mut self, item: Self::Owned, store: &mut Self::Store) {
+        debug_assert!(store[item].next.is_none());
+        debug_assert!(store[item].prev.is_none());
+
+        // Entries are pushed at the tail so that entries sharing a deadline
+        // expire in insertion order.
+        let prev = self.tail.replace(item);
+        match prev {
+            Some(tail) => store[tail].next = Some(item),
+            None => self.head = Some(item),
+        }
+        store[item].prev = prev;
+    }
+
+    fn pop(&mut self, store: &mut Self::Store) -> Option<Self::Owned> {
+        let index = self.head?;
+        self.head = store[index].next.take();
+
+        match self.head {
+            Some(head) => store[head].prev = None,
+            None => self.tail = None,
+        }
+        Some(index)
+    }
+
+    fn remove(&mut self, item: &Self::Borrowed, store: &mut Self::Store) {
+        let index = *item;
+        let (prev, next) = (store[index].prev.take(), store[index].next.take());
+
+        match next {
+            Some(next) => store[next].prev = prev,
+            None => self.tail = prev,
+        }
+        match prev {
+            Some(prev) => store[prev].next = next,
+            None => self.head = next,
+        }
+    }
+
+    fn when(item: &Self::Borrowed, store: &Self::Store) -> u64 {
+        store[*item].when
+    }
+
+    fn min_when(&self, store: &Self::Store) -> Option<u64> {
+        let mut next = self.head;
+        let mut min = None;
+
+        while let Some(index) = next {
+            let node = &store[index];
+            min = Some(min.map_or(node.when, |min: u64| min.min(node.when)));
+            next = node.next;
+        }
+
+        min
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self {
+            head: None,
+            tail: None,
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("List")
+            .field("head", &self.head)
+            .field("tail", &self.tail)
+            .finish()
+    }
+}
+
+impl<T> Default for TimerWheel<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for TimerWheel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimerWheel")
+            .field("elapsed", &self.wheel.elapsed())
+            .field("len", &self.store.len())
+            .finish()
+    }
+}
+
+impl<T> fmt::Debug for Advance<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Advance")
+            .field("wheel", &self.wheel)
+            .field("now", &self.now)
+            .finish()
+    }
+}
@@ -0,0 +1,520 @@
+//! A cache whose entries expire after a time-to-live.
+//!
+//! See [`TtlCache`] for more details.
+
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::task::{self, Poll};
+
+use crate::util::saturating_add;
+use crate::{Delay, DelayQueue, Duration, Expired, Instant, Key};
+
+/// The reason an entry was evicted from a [`TtlCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionCause {
+    /// The time-to-live of the entry elapsed.
+    Expired,
+
+    /// The entry was the least recently used one when a new entry was
+    /// inserted into a full cache.
+    Capacity,
+}
+
+type EvictionListener<K, V> = Box<dyn FnMut(K, V, EvictionCause) + Send>;
+
+/// A cache whose entries expire after a time-to-live, with an optional
+/// capacity bound enforced by evicting the least recently used entry.
+///
+/// The entries are tracked with a [`DelayQueue`], so the cache works with
+/// every [`Delay`] implementation. Expired entries are never returned by the
+/// lookup methods, but they are only removed from the cache, and reported to
+/// the eviction listener, by [`run_expiry`] and [`poll_expiry`], which the
+/// owner of the cache is expected to drive alongside its other work.
+///
+/// With sliding expiration enabled, looking an entry up with [`get`] or
+/// [`get_mut`] restarts its time-to-live.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use timer_kit::TtlCache;
+///
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
+/// let mut cache = TtlCache::<smol::Timer, &str, u32>::builder()
+///     .capacity(1024)
+///     .sliding_expiration(true)
+///     .eviction_listener(|key, value, cause| {
+///         println!("{} = {} evicted: {:?}", key, value, cause);
+///     })
+///     .build();
+///
+/// cache.insert_with_ttl("foo", 1, Duration::from_secs(30));
+/// assert_eq!(cache.get("foo"), Some(&1));
+///
+/// // Removes the entries as they expire
+/// loop {
+///     cache.run_expiry().await;
+/// }
+/// # }
+/// ```
+///
+/// [`run_expiry`]: method@Self::run_expiry
+/// [`poll_expiry`]: method@Self::poll_expiry
+/// [`get`]: method@Self::get
+/// [`get_mut`]: method@Self::get_mut
+pub struct TtlCache<D: Delay, K, V> {
+    queue: DelayQueue<D, K>,
+    entries: HashMap<K, Entry<V>>,
+
+    /// Keys of the entries ordered by their last use
+    lru: BTreeMap<u64, K>,
+
+    /// Counter used to order the uses of the entries
+    uses: u64,
+
+    capacity: Option<usize>,
+    sliding_expiration: bool,
+    listener: Option<EvictionListener<K, V>>,
+    expired: Vec<Expired<K, D::Instant>>,
+}
+
+struct Entry<V> {
+    value: V,
+    key: Key,
+    ttl: Duration,
+    last_used: u64,
+}
+
+/// Builder for a [`TtlCache`].
+pub struct TtlCacheBuilder<D, K, V> {
+    capacity: Option<usize>,
+    sliding_expiration: bool,
+    resolution: Option<Duration>,
+    listener: Option<EvictionListener<K, V>>,
+    _p: PhantomData<fn() -> D>,
+}
+
+impl<D, K, V> TtlCacheBuilder<D, K, V>
+where
+    D: Delay,
+    D::Instant: Unpin,
+    K: Hash + Eq + Clone,
+{
+    /// Creates a new builder for an unbounded cache without sliding
+    /// expiration.
+    pub fn new() -> Self {
+        Self {
+            capacity: None,
+            sliding_expiration: false,
+            resolution: None,
+            listener: None,
+            _p: PhantomData,
+        }
+    }
+
+    /// Sets the maximum number of entries of the cache.
+    ///
+    /// Inserting a new entry into a full cache removes the expired entries,
+    /// or evicts the least recently used entry if none has expired.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `capacity` is zero.
+    #[track_caller]
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be non-zero");
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Sets whether looking an entry up restarts its time-to-live.
+    pub fn sliding_expiration(mut self, sliding: bool) -> Self {
+        self.sliding_expiration = sliding;
+        self
+    }
+
+    /// Sets the duration of a single tick of the underlying [`DelayQueue`].
+    ///
+    /// See [`DelayQueueBuilder::resolution`](crate::DelayQueueBuilder::resolution).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `resolution` is zero.
+    #[track_caller]
+    pub fn resolution(mut self, resolution: Duration) -> Self {
+        assert!(resolution > Duration::new(0, 0), "resolution must be non-zero");
+        self.resolution = Some(resolution);
+        self
+    }
+
+    /// Sets a callback that is called with every entry evicted from the
+    /// cache, along with the reason of the eviction.
+    ///
+    /// Entries that are removed with [`TtlCache::remove`], replaced or
+    /// cleared are not reported.
+    pub fn eviction_listener<F>(mut self, listener: F) -> Self
+    where
+        F: FnMut(K, V, EvictionCause) + Send + 'static,
+    {
+        self.listener = Some(Box::new(listener));
+        self
+    }
+
+    /// Creates the configured [`TtlCache`].
+    pub fn build(self) -> TtlCache<D, K, V> {
+        let mut queue = DelayQueue::builder().capacity(self.capacity.unwrap_or(0));
+        if let Some(resolution) = self.resolution {
+            queue = queue.resolution(resolution);
+        }
+
+        TtlCache {
+            queue: queue.build(),
+            entries: HashMap::with_capacity(self.capacity.unwrap_or(0)),
+            lru: BTreeMap::new(),
+            uses: 0,
+            capacity: self.capacity,
+            sliding_expiration: self.sliding_expiration,
+            listener: self.listener,
+            expired: Vec::new(),
+        }
+    }
+}
+
+impl<D, K, V> TtlCache<D, K, V>
+where
+    D: Delay,
+    D::Instant: Unpin,
+    K: Hash + Eq + Clone,
+{
+    /// Creates a new, empty, unbounded `TtlCache` without sliding expiration.
+    pub fn new() -> Self {
+        TtlCacheBuilder::new().build()
+    }
+
+    /// Creates a new, empty, `TtlCache` that holds at most `capacity`
+    /// entries.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `capacity` is zero.
+    #[track_caller]
+    pub fn with_capacity(capacity: usize) -> Self {
+        TtlCacheBuilder::new().capacity(capacity).build()
+    }
+
+    /// Creates a [`TtlCacheBuilder`] to configure a new `TtlCache`.
+    pub fn builder() -> TtlCacheBuilder<D, K, V> {
+        TtlCacheBuilder::new()
+    }
+
+    /// Inserts `value` for `key`, set to expire after `ttl`.
+    ///
+    /// If the cache already has a live entry for `key`, its value is replaced
+    /// and its time-to-live restarts. The old value is returned. If the cache
+    /// is full, the expired entries are removed first, and the least recently
+    /// used entry is evicted if none has expired.
+    ///
+    /// # Panics
+    ///
+    /// This function panics under the same conditions as [`DelayQueue::insert`].
+    #[track_caller]
+    pub fn insert_with_ttl(&mut self, key: K, value: V, ttl: Duration) -> Option<V> {
+        let now = D::Instant::now();
+        self.evict_if_expired(&key, now);
+
+        let last_used = self.next_use();
+        if let Some(entry) = self.entries.get_mut(&key) {
+            self.queue.reset_at(&entry.key, saturating_add(now, ttl));
+            let lru_key = self.lru.remove(&entry.last_used).expect("entry is in the LRU list");
+            self.lru.insert(last_used, lru_key);
+            entry.last_used = last_used;
+            entry.ttl = ttl;
+            return Some(std::mem::replace(&mut entry.value, value));
+        }
+
+        if self.is_full() {
+            // Expired entries that have not been removed yet make room before
+            // a live entry is evicted
+            self.evict_expired();
+            if self.is_full() {
+                self.evict_least_recently_used();
+            }
+        }
+
+        let queue_key = self.queue.insert_at(key.clone(), saturating_add(now, ttl));
+        self.lru.insert(last_used, key.clone());
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                key: queue_key,
+                ttl,
+                last_used,
+            },
+        );
+        None
+    }
+
+    /// Returns a reference to the value for `key` if it has not expired.
+    ///
+    /// This marks the entry as the most recently used one and, with sliding
+    /// expiration, restarts its time-to-live.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    /// Returns a mutable reference to the value for `key` if it has not
+    /// expired.
+    ///
+    /// This marks the entry as the most recently used one and, with sliding
+    /// expiration, restarts its time-to-live.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let now = D::Instant::now();
+        let last_used = self.next_use();
+        let entry = self.entries.get_mut(key)?;
+        if is_expired(&self.queue, entry, now) {
+            return None;
+        }
+
+        let lru_key = self.lru.remove(&entry.last_used).expect("entry is in the LRU list");
+        self.lru.insert(last_used, lru_key);
+        entry.last_used = last_used;
+
+        if self.sliding_expiration {
+            self.queue.reset_at(&entry.key, saturating_add(now, entry.ttl));
+        }
+        Some(&mut entry.value)
+    }
+
+    /// Returns a reference to the value for `key` if it has not expired,
+    /// without marking it as used or restarting its time-to-live.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let entry = self.entries.get(key)?;
+        if is_expired(&self.queue, entry, D::Instant::now()) {
+            return None;
+        }
+        Some(&entry.value)
+    }
+
+    /// Returns `true` if the cache has an entry for `key` that has not
+    /// expired.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.peek(key).is_some()
+    }
+
+    /// Returns the instant at which the entry for `key` expires.
+    pub fn deadline<Q>(&self, key: &Q) -> Option<D::Instant>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let entry = self.entries.get(key)?;
+        self.queue.deadline(&entry.key)
+    }
+
+    /// Removes the entry for `key`, returning its value if it has not
+    /// expired.
+    ///
+    /// An expired entry is reported to the eviction listener instead.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let expired = is_expired(&self.queue, self.entries.get(key)?, D::Instant::now());
+        let (key, entry) = self.entries.remove_entry(key)?;
+        self.lru.remove(&entry.last_used);
+        self.queue.remove(&entry.key);
+
+        if expired {
+            self.notify(key, entry.value, EvictionCause::Expired);
+            return None;
+        }
+        Some(entry.value)
+    }
+
+    /// Returns the number of entries in the cache, including the expired
+    /// entries that have not been removed yet.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no entries in the cache.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the maximum number of entries of the cache, if it is bounded.
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    /// Removes all entries from the cache without reporting them to the
+    /// eviction listener.
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.entries.clear();
+        self.lru.clear();
+    }
+
+    /// Removes the entries whose time-to-live has elapsed, reporting them to
+    /// the eviction listener, and registers the current task for wakeup when
+    /// the next entry expires.
+    ///
+    /// Returns the number of removed entries, and `Poll::Pending` if no entry
+    /// has expired.
+    pub fn poll_expiry(&mut self, cx: &mut task::Context<'_>) -> Poll<usize> {
+        let mut expired = std::mem::take(&mut self.expired);
+        let count = match self.queue.poll_expired_batch(cx, &mut expired, usize::MAX) {
+            Poll::Ready(0) | Poll::Pending => {
+                self.expired = expired;
+                return Poll::Pending;
+            }
+            Poll::Ready(count) => count,
+        };
+
+        for expired in expired.drain(..) {
+            self.remove_expired(expired.into_inner());
+        }
+        self.expired = expired;
+        Poll::Ready(count)
+    }
+
+    /// Waits until at least one entry expires, then removes the expired
+    /// entries and reports them to the eviction listener.
+    ///
+    /// Returns the number of removed entries. This never completes while the
+    /// cache is empty, and is meant to be called in a loop, for example
+    /// alongside the other work of the owner of the cache in a `select!`.
+    /// Dropping the returned future before it completes does not lose any
+    /// entry.
+    pub async fn run_expiry(&mut self) -> usize {
+        futures_util::future::poll_fn(|cx| self.poll_expiry(cx)).await
+    }
+
+    fn next_use(&mut self) -> u64 {
+        self.uses += 1;
+        self.uses
+    }
+
+    /// Evicts the entry for `key` if it has expired but has not been removed
+    /// yet.
+    fn evict_if_expired(&mut self, key: &K, now: D::Instant) {
+        if self
+            .entries
+            .get(key)
+            .is_some_and(|entry| is_expired(&self.queue, entry, now))
+        {
+            self.remove(key);
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.capacity.is_some_and(|capacity| self.entries.len() >= capacity)
+    }
+
+    /// Removes the entries whose time-to-live has elapsed without waiting.
+    fn evict_expired(&mut self) {
+        for expired in self.queue.drain_expired() {
+            self.remove_expired(expired.into_inner());
+        }
+    }
+
+    /// Removes the entry for `key`, which has been pulled out of the queue
+    /// once its time-to-live elapsed.
+    fn remove_expired(&mut self, key: K) {
+        if let Some(entry) = self.entries.remove(&key) {
+            self.lru.remove(&entry.last_used);
+            self.notify(key, entry.value, EvictionCause::Expired);
+        }
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some((_, key)) = self.lru.pop_first() {
+            let entry = self.entries.remove(&key).expect("entry is in the cache");
+            self.queue.remove(&entry.key);
+            self.notify(key, entry.value, EvictionCause::Capacity);
+        }
+    }
+
+    fn notify(&mut self, key: K, value: V, cause: EvictionCause) {
+        if let Some(listener) = &mut self.listener {
+            listener(key, value, cause);
+        }
+    }
+}
+
+fn is_expired<D, K, V>(queue: &DelayQueue<D, K>, entry: &Entry<V>, now: D::Instant) -> bool
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    queue.deadline(&entry.key).is_none_or(|deadline| deadline <= now)
+}
+
+impl<D, K, V> Default for TtlCache<D, K, V>
+where
+    D: Delay,
+    D::Instant: Unpin,
+    K: Hash + Eq + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D, K, V> Default for TtlCacheBuilder<D, K, V>
+where
+    D: Delay,
+    D::Instant: Unpin,
+    K: Hash + Eq + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D, K, V> fmt::Debug for TtlCache<D, K, V>
+where
+    D: Delay,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TtlCache")
+            .field("len", &self.entries.len())
+            .field("capacity", &self.capacity)
+            .field("sliding_expiration", &self.sliding_expiration)
+            .finish()
+    }
+}
+
+impl<D, K, V> fmt::Debug for TtlCacheBuilder<D, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TtlCacheBuilder")
+            .field("capacity", &self.capacity)
+            .field("sliding_expiration", &self.sliding_expiration)
+            .field("resolution", &self.resolution)
+            .field("eviction_listener", &self.listener.is_some())
+            .finish()
+    }
+}
@@ -1,32 +1,52 @@
-use std::time::Duration;
+use core::{cmp, time::Duration};
+
+use crate::Instant;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
 
 pub(crate) enum Round {
     Up,
     Down,
 }
 
-/// Convert a `Duration` to milliseconds, rounding up and saturating at
+/// Convert a `Duration` to a number of ticks of `resolution`, saturating at
 /// `u64::MAX`.
 ///
-/// The saturating is fine because `u64::MAX` milliseconds are still many
-/// million years.
-/// 
+/// The saturating is fine because `u64::MAX` ticks are still many million
+/// years even at a resolution of 1 microsecond.
+///
 /// # Ref
-/// 
-/// This is copied from `tokio-util::time::ms`:
+///
+/// This is adapted from `tokio-util::time::ms`
 #[inline]
-pub(crate) fn ms(duration: Duration, round: Round) -> u64 {
-    const NANOS_PER_MILLI: u32 = 1_000_000;
-    const MILLIS_PER_SEC: u64 = 1_000;
-
-    // Round up.
-    let millis = match round {
-        Round::Up => (duration.subsec_nanos() + NANOS_PER_MILLI - 1) / NANOS_PER_MILLI,
-        Round::Down => duration.subsec_millis(),
+pub(crate) fn ticks(duration: Duration, resolution: Duration, round: Round) -> u64 {
+    let nanos = duration.as_nanos();
+    let resolution = resolution.as_nanos();
+
+    let ticks = match round {
+        Round::Up => nanos.div_ceil(resolution),
+        Round::Down => nanos / resolution,
     };
 
-    duration
-        .as_secs()
-        .saturating_mul(MILLIS_PER_SEC)
-        .saturating_add(u64::from(millis))
+    u64::try_from(ticks).unwrap_or(u64::MAX)
+}
+
+/// Convert a number of ticks of `resolution` back to a `Duration`, saturating
+/// at the maximum `Duration`.
+#[inline]
+pub(crate) fn duration_from_ticks(ticks: u64, resolution: Duration) -> Duration {
+    let nanos = u128::from(ticks) * resolution.as_nanos();
+    let secs = u64::try_from(nanos / NANOS_PER_SEC).unwrap_or(u64::MAX);
+    let subsec_nanos = (nanos % NANOS_PER_SEC) as u32;
+
+    Duration::new(secs, subsec_nanos)
+}
+
+/// Returns `instant + duration`, saturating at [`Instant::far_future`] instead
+/// of overflowing.
+#[inline]
+pub(crate) fn saturating_add<I: Instant>(instant: I, duration: Duration) -> I {
+    instant
+        .checked_add(duration)
+        .unwrap_or_else(|| cmp::max(instant, I::far_future()))
 }
@@ -0,0 +1,481 @@
+//! Watchdogs that fire when they are not fed in time, and monitors of the heartbeats of many
+//! peers.
+//!
+//! See [`Watchdog`] and [`HeartbeatMonitor`] for more details.
+
+use std::{
+    borrow::Borrow,
+    fmt,
+    future::{poll_fn, Future},
+    hash::Hash,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use futures_util::Stream;
+
+use crate::{util::saturating_add, Delay, DelayMap, Instant, Sleep};
+
+/// A timer that must be fed within a timeout, or it reports that it has been missed.
+///
+/// A `Watchdog` is fed with [`feed`](Self::feed), or from other tasks and threads with the
+/// cloneable [`Feeder`] handles returned by [`feeder`](Self::feeder). Every feed pushes the
+/// deadline of the watchdog to one timeout after the feed.
+///
+/// Once the deadline is reached without a feed, the watchdog yields a [`Missed`] event, and arms
+/// itself again one timeout later. The events count the consecutive misses since the last feed,
+/// so that a component that stays silent is reported once per timeout until it is fed again.
+///
+/// The events can be awaited one at a time with [`missed`](Self::missed), consumed with the
+/// [`Stream`] implementation, which never ends, or handed to a callback with
+/// [`run`](Self::run).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use timer_kit::Watchdog;
+///
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
+/// let mut watchdog = Watchdog::<smol::Timer>::new(Duration::from_secs(1));
+///
+/// let feeder = watchdog.feeder();
+/// smol::spawn(async move {
+///     loop {
+///         // ... do some work
+///         feeder.feed();
+///     }
+/// })
+/// .detach();
+///
+/// let missed = watchdog.missed().await;
+/// println!("the worker has been silent {} times in a row", missed.count());
+/// # }
+/// ```
+pub struct Watchdog<D: Delay> {
+    sleep: Pin<Box<Sleep<D>>>,
+    timeout: Duration,
+
+    /// Number of consecutive misses since the last feed
+    missed: u64,
+
+    /// Instant of the last feed seen by the watchdog
+    last_fed: D::Instant,
+
+    fed: Arc<Mutex<Fed<D::Instant>>>,
+}
+
+/// A handle that feeds a [`Watchdog`] from another task or thread.
+///
+/// This is created by [`Watchdog::feeder`].
+pub struct Feeder<D: Delay> {
+    fed: Arc<Mutex<Fed<D::Instant>>>,
+}
+
+/// The feeds of a [`Watchdog`] by its [`Feeder`]s that the watchdog has not seen yet
+struct Fed<I> {
+    /// Instant of the last feed
+    last: Option<I>,
+
+    /// Waker of the task polling the watchdog
+    waker: Option<Waker>,
+}
+
+/// A deadline of a [`Watchdog`] that has been missed.
+///
+/// Values are returned by [`Watchdog::poll_missed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Missed<I: Instant> {
+    /// The deadline that was missed
+    deadline: I,
+
+    /// Number of consecutive misses since the last feed
+    count: u64,
+}
+
+fn lock<I>(fed: &Mutex<Fed<I>>) -> MutexGuard<'_, Fed<I>> {
+    // The state is consistent even if a task panicked while holding the lock
+    fed.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+impl<D> Watchdog<D>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    /// Creates a new `Watchdog` that must be fed within `timeout`, starting now.
+    pub fn new(timeout: Duration) -> Self {
+        let now = D::Instant::now();
+        Self {
+            sleep: Box::pin(Sleep::new_until(saturating_add(now, timeout))),
+            timeout,
+            missed: 0,
+            last_fed: now,
+            fed: Arc::new(Mutex::new(Fed {
+                last: None,
+                waker: None,
+            })),
+        }
+    }
+
+    /// Returns the timeout of the watchdog.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Returns the instant at which the watchdog fires if it is not fed.
+    ///
+    /// The feeds by the [`Feeder`]s are only taken into account once the watchdog is polled.
+    pub fn deadline(&self) -> D::Instant {
+        self.sleep.deadline()
+    }
+
+    /// Returns the number of consecutive misses since the last feed.
+    pub fn missed_count(&self) -> u64 {
+        self.missed
+    }
+
+    /// Feeds the watchdog, moving its deadline to one timeout from now.
+    pub fn feed(&mut self) {
+        self.feed_at(D::Instant::now());
+    }
+
+    /// Returns a handle that feeds the watchdog from another task or thread.
+    pub fn feeder(&self) -> Feeder<D> {
+        Feeder {
+            fed: self.fed.clone(),
+        }
+    }
+
+    /// Polls for the next missed deadline.
+    ///
+    /// Once a deadline is missed, the watchdog is armed again one timeout later. This never
+    /// returns `Poll::Ready` while the watchdog is fed in time.
+    pub fn poll_missed(&mut self, cx: &mut Context<'_>) -> Poll<Missed<D::Instant>> {
+        let last = {
+            let mut fed = lock(&self.fed);
+            match &fed.waker {
+                Some(waker) if waker.will_wake(cx.waker()) => {}
+                _ => fed.waker = Some(cx.waker().clone()),
+            }
+            fed.last.take()
+        };
+        // A feeder may have fed the watchdog before the last call to `feed`
+        if let Some(last) = last.filter(|last| *last > self.last_fed) {
+            self.feed_at(last);
+        }
+
+        futures_util::ready!(self.sleep.as_mut().poll(cx));
+
+        let deadline = self.sleep.deadline();
+        self.missed += 1;
+        self.sleep.as_mut().reset(saturating_add(deadline, self.timeout));
+
+        Poll::Ready(Missed {
+            deadline,
+            count: self.missed,
+        })
+    }
+
+    /// Waits until the next deadline is missed.
+    ///
+    /// This is the future counterpart of [`poll_missed`](Self::poll_missed).
+    pub async fn missed(&mut self) -> Missed<D::Instant> {
+        poll_fn(|cx| self.poll_missed(cx)).await
+    }
+
+    /// Calls `on_missed` every time a deadline is missed.
+    ///
+    /// This never completes, and is meant to be spawned or to run alongside the other work of
+    /// the owner of the watchdog in a `select!`.
+    pub async fn run<F>(&mut self, mut on_missed: F)
+    where
+        F: FnMut(Missed<D::Instant>),
+    {
+        loop {
+            on_missed(self.missed().await);
+        }
+    }
+
+    fn feed_at(&mut self, at: D::Instant) {
+        self.missed = 0;
+        self.last_fed = at;
+        self.sleep.as_mut().reset(saturating_add(at, self.timeout));
+    }
+}
+
+impl<D: Delay> Feeder<D> {
+    /// Feeds the [`Watchdog`], moving its deadline to one timeout from now.
+    pub fn feed(&self) {
+        let mut fed = lock(&self.fed);
+        fed.last = Some(D::Instant::now());
+        if let Some(waker) = fed.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<I: Instant> Missed<I> {
+    /// Returns the deadline that was missed.
+    pub fn deadline(&self) -> I {
+        self.deadline
+    }
+
+    /// Returns the number of consecutive misses since the last feed, starting at 1.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+impl<D> Stream for Watchdog<D>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    type Item = Missed<D::Instant>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_missed(cx).map(Some)
+    }
+}
+
+impl<D: Delay> Clone for Feeder<D> {
+    fn clone(&self) -> Self {
+        Self {
+            fed: self.fed.clone(),
+        }
+    }
+}
+
+impl<D> fmt::Debug for Watchdog<D>
+where
+    D: Delay + fmt::Debug,
+    D::Instant: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watchdog")
+            .field("sleep", &self.sleep)
+            .field("timeout", &self.timeout)
+            .field("missed", &self.missed)
+            .field("last_fed", &self.last_fed)
+            .finish()
+    }
+}
+
+impl<D: Delay> fmt::Debug for Feeder<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Feeder").finish_non_exhaustive()
+    }
+}
+
+/// Tracks when many peers were last seen, and reports the peers that go silent.
+///
+/// Every call to [`heartbeat`](Self::heartbeat) records that a peer identified by a key of type
+/// `K` has been seen, and gives it one timeout to send its next heartbeat. The peers are kept in a
+/// [`DelayMap`], so recording a heartbeat does not allocate a timer per peer.
+///
+/// A peer that does not send a heartbeat within the timeout is removed from the monitor and
+/// yielded as a [`Silent`] event by [`poll_silent`] and the [`Stream`] implementation. A later
+/// heartbeat of the peer starts tracking it again. Like a [`DelayMap`], the stream returns
+/// `None` when no peer is tracked.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use futures::StreamExt;
+/// use timer_kit::HeartbeatMonitor;
+///
+/// # #[cfg(feature = "smol")]
+/// # async fn example() {
+/// let mut monitor = HeartbeatMonitor::<smol::Timer, &str>::new(Duration::from_secs(5));
+/// monitor.heartbeat("node-1");
+/// monitor.heartbeat("node-2");
+///
+/// while let Some(silent) = monitor.next().await {
+///     println!("{} has been silent since {:?}", silent.key(), silent.last_seen());
+/// }
+/// # }
+/// ```
+///
+/// [`poll_silent`]: method@Self::poll_silent
+pub struct HeartbeatMonitor<D: Delay, K> {
+    /// Instants at which the peers were last seen
+    peers: DelayMap<D, K, D::Instant>,
+    timeout: Duration,
+}
+
+/// A peer of a [`HeartbeatMonitor`] that has gone silent.
+///
+/// Values are returned by [`HeartbeatMonitor::poll_silent`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Silent<K, I: Instant> {
+    /// The key of the peer
+    key: K,
+
+    /// The instant at which the peer was last seen
+    last_seen: I,
+}
+
+impl<D, K> HeartbeatMonitor<D, K>
+where
+    D: Delay,
+    D::Instant: Unpin,
+    K: Hash + Eq + Clone,
+{
+    /// Creates a new `HeartbeatMonitor` whose peers go silent after `timeout` without a
+    /// heartbeat.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            peers: DelayMap::new(),
+            timeout,
+        }
+    }
+
+    /// Returns the timeout after which a peer goes silent.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Records a heartbeat of the peer `key` now.
+    ///
+    /// Returns the instant at which the peer was last seen, or `None` if the peer was not
+    /// tracked.
+    ///
+    /// # Panics
+    ///
+    /// This function panics under the same conditions as [`DelayMap::insert`].
+    #[track_caller]
+    pub fn heartbeat(&mut self, key: K) -> Option<D::Instant> {
+        self.heartbeat_at(key, D::Instant::now())
+    }
+
+    /// Records a heartbeat of the peer `key` at `seen`.
+    ///
+    /// Returns the instant at which the peer was last seen, or `None` if the peer was not
+    /// tracked.
+    ///
+    /// # Panics
+    ///
+    /// This function panics under the same conditions as [`DelayMap::insert_at`].
+    #[track_caller]
+    pub fn heartbeat_at(&mut self, key: K, seen: D::Instant) -> Option<D::Instant> {
+        self.peers.insert_at(key, seen, saturating_add(seen, self.timeout))
+    }
+
+    /// Stops tracking the peer `key`.
+    ///
+    /// Returns the instant at which the peer was last seen, or `None` if the peer was not
+    /// tracked.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<D::Instant>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.peers.remove(key)
+    }
+
+    /// Returns the instant at which the peer `key` was last seen.
+    pub fn last_seen<Q>(&self, key: &Q) -> Option<D::Instant>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.peers.get(key).copied()
+    }
+
+    /// Returns the instant at which the peer `key` goes silent without another heartbeat.
+    pub fn deadline<Q>(&self, key: &Q) -> Option<D::Instant>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.peers.deadline(key)
+    }
+
+    /// Returns `true` if the peer `key` is tracked.
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.peers.contains_key(key)
+    }
+
+    /// Returns an iterator over the tracked peers and the instants at which they were last seen,
+    /// in arbitrary order.
+    pub fn peers(&self) -> impl Iterator<Item = (&K, D::Instant)> + '_ {
+        self.peers.iter().map(|(key, last_seen, _)| (key, *last_seen))
+    }
+
+    /// Returns the number of tracked peers.
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    /// Returns `true` if no peer is tracked.
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    /// Stops tracking every peer.
+    pub fn clear(&mut self) {
+        self.peers.clear();
+    }
+
+    /// Attempts to pull out the next peer that has gone silent.
+    ///
+    /// `Poll::Ready(None)` is returned when no peer is tracked.
+    pub fn poll_silent(&mut self, cx: &mut Context<'_>) -> Poll<Option<Silent<K, D::Instant>>> {
+        self.peers
+            .poll_expired(cx)
+            .map(|silent| silent.map(|(key, last_seen)| Silent { key, last_seen }))
+    }
+}
+
+impl<K, I: Instant> Silent<K, I> {
+    /// Returns a reference to the key of the peer.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Consumes `self` and returns the key of the peer.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Returns the instant at which the peer was last seen.
+    pub fn last_seen(&self) -> I {
+        self.last_seen
+    }
+}
+
+impl<D, K> Stream for HeartbeatMonitor<D, K>
+where
+    D: Delay,
+    D::Instant: Unpin,
+    K: Hash + Eq + Clone,
+{
+    type Item = Silent<K, D::Instant>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_silent(cx)
+    }
+}
+
+impl<D, K> fmt::Debug for HeartbeatMonitor<D, K>
+where
+    D: Delay + fmt::Debug,
+    D::Instant: fmt::Debug,
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HeartbeatMonitor")
+            .field("peers", &self.peers)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
@@ -1,6 +1,6 @@
 use super::Stack;
 
-use std::fmt;
+use core::fmt;
 
 /// Wheel for a single level in the timer. This wheel contains 64 slots.
 pub(crate) struct Level<T> {
@@ -127,10 +127,7 @@ impl<T: Stack> Level<T> {
     pub(crate) fn next_expiration(&self, now: u64) -> Option<Expiration> {
         // Use the `occupied` bit field to get the index of the next slot that
         // needs to be processed.
-        let slot = match self.next_occupied_slot(now) {
-            Some(slot) => slot,
-            None => return None,
-        };
+        let slot = self.next_occupied_slot(now)?;
 
         // From the slot index, calculate the `Instant` at which it needs to be
         // processed. This value *must* be in the future with respect to `now`.
@@ -140,7 +137,16 @@ impl<T: Stack> Level<T> {
 
         // TODO: This can probably be simplified w/ power of 2 math
         let level_start = now - (now % level_range);
-        let deadline = level_start + slot as u64 * slot_range;
+        let mut deadline = level_start + slot as u64 * slot_range;
+
+        if deadline < now {
+            // An entry that is more than one rotation of the last level ahead of `now` (but still
+            // within `MAX_DURATION`) wraps around the last level's slots, so a slot "prior" to
+            // `now` is actually in the next rotation.
+            debug_assert_eq!(self.level, super::NUM_LEVELS - 1);
+
+            deadline += level_range;
+        }
 
         debug_assert!(
             deadline >= now,
@@ -194,6 +200,11 @@ impl<T: Stack> Level<T> {
         }
     }
 
+    /// Returns the earliest `when` of the entries in `slot`
+    pub(crate) fn min_when_in_slot(&self, slot: usize, store: &T::Store) -> Option<u64> {
+        self.slot[slot].min_when(store)
+    }
+
     pub(crate) fn pop_entry_slot(&mut self, slot: usize, store: &mut T::Store) -> Option<T::Owned> {
         let ret = self.slot[slot].pop(store);
 
@@ -228,7 +239,7 @@ fn level_range(level: usize) -> u64 {
     LEVEL_MULT as u64 * slot_range(level)
 }
 
-/// Convert a duration (milliseconds) and a level to a slot position
+/// Convert a duration (ticks) and a level to a slot position
 fn slot_for(duration: u64, level: usize) -> usize {
     ((duration >> (level * 6)) % LEVEL_MULT as u64) as usize
 }
@@ -246,7 +257,7 @@ mod test {
         for level in 1..5 {
             for pos in level..64 {
                 let a = pos * 64_usize.pow(level as u32);
-                assert_eq!(pos as usize, slot_for(a as u64, level));
+                assert_eq!(pos, slot_for(a as u64, level));
             }
         }
     }
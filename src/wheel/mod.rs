@@ -5,9 +5,10 @@ use self::level::Level;
 mod stack;
 pub(crate) use self::stack::Stack;
 
-use std::borrow::Borrow;
-use std::fmt::Debug;
-use std::usize;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt::Debug;
 
 /// Timing wheel implementation.
 ///
@@ -22,12 +23,12 @@ use std::usize;
 /// See `Timer` documentation for some implementation notes.
 #[derive(Debug)]
 pub(crate) struct Wheel<T> {
-    /// The number of milliseconds elapsed since the wheel started.
+    /// The number of ticks elapsed since the wheel started.
     elapsed: u64,
 
     /// Timer wheel.
     ///
-    /// Levels:
+    /// Levels (with a tick of 1 ms):
     ///
     /// * 1 ms slots / 64 ms range
     /// * 64 ms slots / ~ 4 sec range
@@ -36,20 +37,42 @@ pub(crate) struct Wheel<T> {
     /// * ~ 4 hr slots / ~ 12 day range
     /// * ~ 12 day slots / ~ 2 yr range
     levels: Vec<Level<T>>,
+
+    /// Entries whose deadline is beyond the range of the levels, keyed by
+    /// their deadline. They are moved into the levels once the wheel has
+    /// advanced close enough to their deadline.
+    overflow: BTreeMap<u64, T>,
+
+    /// Overflowing entries that were already due when they were moved out of
+    /// the overflow bucket. They are returned before any other entry.
+    expired: T,
+
+    /// Set when overflowing entries were moved into the levels while a slot
+    /// of a higher level may still be due. Until the due slots have been
+    /// processed, the earliest expiration is not necessarily in the lowest
+    /// occupied level.
+    unordered: bool,
 }
 
 /// Number of levels. Each level has 64 slots. By using 6 levels with 64 slots
-/// each, the timer is able to track time up to 2 years into the future with a
-/// precision of 1 millisecond.
+/// each, the timer is able to track time up to 2^36 ticks into the future,
+/// which is about 2 years with a tick of 1 millisecond. Entries further into
+/// the future are kept in the overflow bucket.
 const NUM_LEVELS: usize = 6;
 
-/// The maximum duration of a delay
-const MAX_DURATION: u64 = (1 << (6 * NUM_LEVELS)) - 1;
+/// The number of ticks covered by one rotation of the last level.
+const LEVELS_RANGE: u64 = 1 << (6 * NUM_LEVELS);
+
+/// The maximum duration of a delay stored in the levels.
+///
+/// This is one slot of the last level short of `LEVELS_RANGE`, so that an
+/// entry never wraps around into the slot of the last level that `elapsed` is
+/// in, where it would hide the other slots of that level.
+pub(crate) const MAX_DURATION: u64 = LEVELS_RANGE - (LEVELS_RANGE >> 6);
 
 #[derive(Debug)]
 pub(crate) enum InsertError {
     Elapsed,
-    Invalid,
 }
 
 impl<T> Wheel<T>
@@ -60,11 +83,17 @@ where
     pub(crate) fn new() -> Wheel<T> {
         let levels = (0..NUM_LEVELS).map(Level::new).collect();
 
-        Wheel { elapsed: 0, levels }
+        Wheel {
+            elapsed: 0,
+            levels,
+            overflow: BTreeMap::new(),
+            expired: T::default(),
+            unordered: false,
+        }
     }
 
-    /// Return the number of milliseconds that have elapsed since the timing
-    /// wheel's creation.
+    /// Return the number of ticks that have elapsed since the timing wheel's
+    /// creation.
     pub(crate) fn elapsed(&self) -> u64 {
         self.elapsed
     }
@@ -74,8 +103,8 @@ where
     /// # Arguments
     ///
     /// * `when`: is the instant at which the entry should be fired. It is
-    ///           represented as the number of milliseconds since the creation
-    ///           of the timing wheel.
+    ///   represented as the number of ticks since the creation of the
+    ///   timing wheel.
     ///
     /// * `item`: The item to insert into the wheel.
     ///
@@ -89,7 +118,8 @@ where
     /// already passed. In this case, the caller should fire the timeout
     /// immediately.
     ///
-    /// `Err(Invalid)` indicates an invalid `when` argument as been supplied.
+    /// Entries more than `MAX_DURATION` into the future are kept in an
+    /// overflow bucket until the wheel advances close enough to them.
     pub(crate) fn insert(
         &mut self,
         when: u64,
@@ -98,8 +128,9 @@ where
     ) -> Result<(), (T::Owned, InsertError)> {
         if when <= self.elapsed {
             return Err((item, InsertError::Elapsed));
-        } else if when - self.elapsed > MAX_DURATION {
-            return Err((item, InsertError::Invalid));
+        } else if when.saturating_sub(self.elapsed) > MAX_DURATION {
+            self.overflow.entry(when).or_default().push(item, store);
+            return Ok(());
         }
 
         // Get the level at which the entry should be stored
@@ -122,6 +153,11 @@ where
     pub(crate) fn remove(&mut self, item: &T::Borrowed, store: &mut T::Store) {
         let when = T::when(item, store);
 
+        if when <= self.elapsed && !self.expired.is_empty() {
+            self.expired.remove(item, store);
+            return;
+        }
+
         assert!(
             self.elapsed <= when,
             "elapsed={}; when={}",
@@ -129,6 +165,20 @@ where
             when
         );
 
+        if when.saturating_sub(self.elapsed) > MAX_DURATION {
+            let stack = self
+                .overflow
+                .get_mut(&when)
+                .expect("entry is not in the overflow bucket");
+            stack.remove(item, store);
+
+            if stack.is_empty() {
+                self.overflow.remove(&when);
+            }
+
+            return;
+        }
+
         let level = self.level_for(when);
 
         self.levels[level].remove_entry(when, item, store);
@@ -136,11 +186,46 @@ where
 
     /// Instant at which to poll
     pub(crate) fn poll_at(&self) -> Option<u64> {
-        self.next_expiration().map(|expiration| expiration.deadline)
+        if !self.expired.is_empty() {
+            return Some(self.elapsed);
+        }
+
+        let expiration = self.next_expiration().map(|expiration| expiration.deadline);
+
+        // The wheel needs to be polled once the earliest overflowing entry
+        // comes within range so that it can be moved into the levels.
+        let migration = self.next_migration();
+
+        match (expiration, migration) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Returns the earliest deadline of all entries in the wheel.
+    ///
+    /// Unlike [`poll_at`](Self::poll_at), which returns the instant at which
+    /// the next slot must be processed, this is the exact deadline of an entry.
+    pub(crate) fn peek_min_when(&self, store: &T::Store) -> Option<u64> {
+        // Entries in lower levels always expire before entries in higher
+        // levels, and the overflow bucket only holds entries beyond the
+        // levels, so the earliest entry is in the next slot to be processed.
+        if let Some(when) = self.expired.min_when(store) {
+            return Some(when);
+        }
+
+        match self.next_expiration() {
+            Some(expiration) => self.levels[expiration.level].min_when_in_slot(expiration.slot, store),
+            None => self.overflow.keys().next().copied(),
+        }
     }
 
     /// Advances the timer up to the instant represented by `now`.
     pub(crate) fn poll(&mut self, now: u64, store: &mut T::Store) -> Option<T::Owned> {
+        if let Some(item) = self.expired.pop(store) {
+            return Some(item);
+        }
+
         loop {
             let expiration = self.next_expiration().and_then(|expiration| {
                 if expiration.deadline > now {
@@ -156,22 +241,46 @@ where
                         return Some(item);
                     }
 
-                    self.set_elapsed(expiration.deadline);
+                    self.set_elapsed(expiration.deadline, store);
                 }
                 None => {
+                    // No slot is due, so the levels are ordered again
+                    self.unordered = false;
+
+                    // Stop where the earliest overflowing entry comes within
+                    // range, so that it is moved into the levels before it is
+                    // due and fires in order with the other entries.
+                    if let Some(migration) = self.next_migration().filter(|m| *m <= now) {
+                        self.set_elapsed(migration, store);
+                        continue;
+                    }
+
                     // in this case the poll did not indicate an expiration
                     // _and_ we were not able to find a next expiration in
                     // the current list of timers.  advance to the poll's
                     // current time and do nothing else.
-                    self.set_elapsed(now);
-                    return None;
+                    self.set_elapsed(now, store);
+                    return self.expired.pop(store);
                 }
             }
         }
     }
 
+    /// Returns the tick at which the earliest overflowing entry comes within
+    /// range of the levels.
+    fn next_migration(&self) -> Option<u64> {
+        self.overflow
+            .keys()
+            .next()
+            .map(|when| when.saturating_sub(MAX_DURATION))
+    }
+
     /// Returns the instant at which the next timeout expires.
     fn next_expiration(&self) -> Option<Expiration> {
+        if self.unordered {
+            return self.earliest_expiration();
+        }
+
         // Check all levels
         for level in 0..NUM_LEVELS {
             if let Some(expiration) = self.levels[level].next_expiration(self.elapsed) {
@@ -186,6 +295,28 @@ where
         None
     }
 
+    /// Returns the earliest expiration of all levels, preferring the lowest
+    /// level on ties.
+    ///
+    /// An overflowing entry moved into the levels by `set_elapsed` may land in
+    /// a lower level while a slot of a higher level is still due at
+    /// `elapsed`, so the first level with an expiration is not enough.
+    fn earliest_expiration(&self) -> Option<Expiration> {
+        let mut next: Option<Expiration> = None;
+        for level in &self.levels {
+            if let Some(expiration) = level.next_expiration(self.elapsed) {
+                if next
+                    .as_ref()
+                    .is_none_or(|next| expiration.deadline < next.deadline)
+                {
+                    next = Some(expiration);
+                }
+            }
+        }
+
+        next
+    }
+
     /// Used for debug assertions
     fn no_expirations_before(&self, start_level: usize, before: u64) -> bool {
         let mut res = true;
@@ -227,7 +358,7 @@ where
         None
     }
 
-    fn set_elapsed(&mut self, when: u64) {
+    fn set_elapsed(&mut self, when: u64, store: &mut T::Store) {
         assert!(
             self.elapsed <= when,
             "elapsed={:?}; when={:?}",
@@ -237,6 +368,34 @@ where
 
         if when > self.elapsed {
             self.elapsed = when;
+            self.migrate_overflow(store);
+        }
+    }
+
+    /// Moves the overflowing entries that are now within range into the
+    /// levels.
+    fn migrate_overflow(&mut self, store: &mut T::Store) {
+        while let Some(entry) = self.overflow.first_entry() {
+            let when = *entry.key();
+            if when.saturating_sub(self.elapsed) > MAX_DURATION {
+                break;
+            }
+
+            let mut stack = entry.remove();
+
+            // The levels cannot hold entries that are already due
+            if when <= self.elapsed {
+                while let Some(item) = stack.pop(store) {
+                    self.expired.push(item, store);
+                }
+                continue;
+            }
+
+            let level = self.level_for(when);
+            while let Some(item) = stack.pop(store) {
+                self.levels[level].add_entry(when, item, store);
+            }
+            self.unordered = true;
         }
     }
 
@@ -254,7 +413,13 @@ fn level_for(elapsed: u64, when: u64) -> usize {
 
     // Mask in the trailing bits ignored by the level calculation in order to cap
     // the possible leading zeros
-    let masked = elapsed ^ when | SLOT_MASK;
+    let mut masked = elapsed ^ when | SLOT_MASK;
+
+    // `when` may be within `MAX_DURATION` of `elapsed` while still differing in bits above
+    // the last level. Those entries belong to the last level.
+    if masked >= LEVELS_RANGE - 1 {
+        masked = LEVELS_RANGE - 2;
+    }
 
     let leading_zeros = masked.leading_zeros() as usize;
     let significant = 63 - leading_zeros;
@@ -312,4 +477,145 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_level_for_wraps_last_level() {
+        // Within `MAX_DURATION` of `elapsed`, but differing in bits above the last level
+        let elapsed = LEVELS_RANGE - 10;
+        let when = LEVELS_RANGE + 10;
+        assert_eq!(NUM_LEVELS - 1, level_for(elapsed, when));
+    }
+
+    /// A stack backed by a `Vec` whose items carry their own deadline
+    #[derive(Debug, Default)]
+    struct VecStack(Vec<(u64, usize)>);
+
+    impl Stack for VecStack {
+        type Owned = (u64, usize);
+        type Borrowed = (u64, usize);
+        type Store = ();
+
+        fn is_empty(&self) -> bool {
+            self.0.is_empty()
+        }
+
+        fn push(&mut self, item: Self::Owned, _: &mut Self::Store) {
+            self.0.push(item);
+        }
+
+        fn pop(&mut self, _: &mut Self::Store) -> Option<Self::Owned> {
+            self.0.pop()
+        }
+
+        fn remove(&mut self, item: &Self::Borrowed, _: &mut Self::Store) {
+            self.0.retain(|other| other != item);
+        }
+
+        fn when(item: &Self::Borrowed, _: &Self::Store) -> u64 {
+            item.0
+        }
+
+        fn min_when(&self, _: &Self::Store) -> Option<u64> {
+            self.0.iter().map(|item| item.0).min()
+        }
+    }
+
+    #[test]
+    fn test_overflow_is_migrated() {
+        let mut wheel = Wheel::<VecStack>::new();
+        let far = 3 * MAX_DURATION + 7;
+        wheel.insert(far, (far, 0), &mut ()).unwrap();
+        wheel.insert(10, (10, 1), &mut ()).unwrap();
+
+        assert_eq!(wheel.poll_at(), Some(10));
+        assert_eq!(wheel.poll(10, &mut ()), Some((10, 1)));
+
+        // Advance the wheel until the far entry fires
+        let mut polls = 0;
+        let (now, item) = loop {
+            let now = wheel.poll_at().unwrap();
+            assert!(now <= far);
+            if let Some(item) = wheel.poll(now, &mut ()) {
+                break (now, item);
+            }
+            polls += 1;
+        };
+        assert_eq!(item, (far, 0));
+        assert_eq!(now, far);
+        assert!(polls < 64);
+        assert_eq!(wheel.poll_at(), None);
+    }
+
+    #[test]
+    fn test_poll_past_overflow_deadline() {
+        let mut wheel = Wheel::<VecStack>::new();
+        let far = MAX_DURATION + 100;
+        let farther = MAX_DURATION + 200;
+        wheel.insert(farther, (farther, 1), &mut ()).unwrap();
+        wheel.insert(far, (far, 0), &mut ()).unwrap();
+
+        // Jump past both overflowing deadlines in a single poll
+        let now = farther + 10;
+        assert_eq!(wheel.poll(now, &mut ()), Some((far, 0)));
+        assert_eq!(wheel.poll(now, &mut ()), Some((farther, 1)));
+        assert_eq!(wheel.poll(now, &mut ()), None);
+        assert_eq!(wheel.elapsed(), now);
+        assert_eq!(wheel.poll_at(), None);
+    }
+
+    #[test]
+    fn test_far_entries_do_not_hide_earlier_slots() {
+        let mut wheel = Wheel::<VecStack>::new();
+        let elapsed = (9 << 30) + 1000;
+        assert_eq!(wheel.poll(elapsed, &mut ()), None);
+
+        // Without the overflow bucket, `far` would share the slot of the last
+        // level that `elapsed` is in and hide the slot of `near`
+        let near = 63 << 30;
+        let far = elapsed + LEVELS_RANGE - 1;
+        wheel.insert(far, (far, 0), &mut ()).unwrap();
+        wheel.insert(near, (near, 1), &mut ()).unwrap();
+        assert!(wheel.poll_at().unwrap() <= near);
+
+        let mut fired = Vec::new();
+        while let Some(at) = wheel.poll_at() {
+            if let Some(item) = wheel.poll(at, &mut ()) {
+                fired.push(item);
+            }
+        }
+        assert_eq!(fired, [(near, 1), (far, 0)]);
+    }
+
+    #[test]
+    fn test_remove_overflow() {
+        let mut wheel = Wheel::<VecStack>::new();
+        let far = 2 * MAX_DURATION;
+        wheel.insert(far, (far, 0), &mut ()).unwrap();
+        assert_eq!(wheel.poll_at(), Some(far - MAX_DURATION));
+
+        wheel.remove(&(far, 0), &mut ());
+        assert_eq!(wheel.poll_at(), None);
+    }
+
+    #[test]
+    fn test_peek_min_when() {
+        let mut wheel = Wheel::<VecStack>::new();
+        assert_eq!(wheel.peek_min_when(&()), None);
+
+        let far = 2 * MAX_DURATION;
+        wheel.insert(far, (far, 0), &mut ()).unwrap();
+        assert_eq!(wheel.peek_min_when(&()), Some(far));
+
+        // Both entries share a slot on level 1
+        wheel.insert(100, (100, 1), &mut ()).unwrap();
+        wheel.insert(90, (90, 2), &mut ()).unwrap();
+        assert_eq!(wheel.poll_at(), Some(64));
+        assert_eq!(wheel.peek_min_when(&()), Some(90));
+
+        wheel.insert(5000, (5000, 3), &mut ()).unwrap();
+        assert_eq!(wheel.peek_min_when(&()), Some(90));
+
+        wheel.remove(&(90, 2), &mut ());
+        assert_eq!(wheel.peek_min_when(&()), Some(100));
+    }
 }
@@ -1,6 +1,6 @@
-use std::borrow::Borrow;
-use std::cmp::Eq;
-use std::hash::Hash;
+use core::borrow::Borrow;
+use core::cmp::Eq;
+use core::hash::Hash;
 
 /// Abstracts the stack operations needed to track timeouts.
 pub(crate) trait Stack: Default {
@@ -25,4 +25,7 @@ pub(crate) trait Stack: Default {
     fn remove(&mut self, item: &Self::Borrowed, store: &mut Self::Store);
 
     fn when(item: &Self::Borrowed, store: &Self::Store) -> u64;
+
+    /// Returns the earliest `when` of the items in the stack
+    fn min_when(&self, store: &Self::Store) -> Option<u64>;
 }
@@ -0,0 +1,119 @@
+#![allow(dead_code, unused_imports)]
+
+use std::time::Duration;
+
+use timer_kit::{blocking, Delay, DelayQueue, Instant, MissedTickBehavior};
+
+pub fn sleep_until<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let deadline = D::Instant::now() + Duration::from_millis(100);
+    blocking::sleep_until::<D>(deadline);
+    let now = D::Instant::now();
+    assert!(now >= deadline);
+    assert!(now - deadline < Duration::from_millis(10));
+}
+
+pub fn sleep<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let start = D::Instant::now();
+    blocking::sleep::<D>(Duration::from_millis(100));
+    let elapsed = D::Instant::now() - start;
+    assert!(elapsed >= Duration::from_millis(100));
+    assert!(elapsed < Duration::from_millis(110));
+}
+
+pub fn tick_blocking<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let start = D::Instant::now();
+    let mut interval = timer_kit::interval_at::<D>(start, Duration::from_millis(50));
+    for _ in interval.iter_blocking().take(3) {}
+
+    let elapsed = D::Instant::now() - start;
+    assert!(elapsed >= Duration::from_millis(100));
+    assert!(elapsed < Duration::from_millis(110));
+}
+
+pub fn tick_blocking_skips_missed_ticks<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let start = D::Instant::now();
+    let mut interval = timer_kit::interval_at::<D>(start, Duration::from_millis(100));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    interval.tick_blocking();
+
+    // Miss two ticks, the next one is at 300ms
+    std::thread::sleep(Duration::from_millis(250));
+    interval.tick_blocking();
+    interval.tick_blocking();
+
+    let elapsed = D::Instant::now() - start;
+    assert!(elapsed >= Duration::from_millis(300));
+    assert!(elapsed < Duration::from_millis(310));
+}
+
+pub fn next_expired_blocking<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = DelayQueue::<D, &str>::new();
+    queue.insert("second", Duration::from_millis(100));
+    queue.insert("first", Duration::from_millis(50));
+
+    let expired = queue.next_expired_blocking(Duration::from_secs(1)).unwrap().unwrap();
+    assert_eq!(*expired.get_ref(), "first");
+    let expired = queue.next_expired_blocking(Duration::from_secs(1)).unwrap().unwrap();
+    assert_eq!(*expired.get_ref(), "second");
+
+    assert!(queue.next_expired_blocking(Duration::from_secs(1)).unwrap().is_none());
+}
+
+pub fn next_expired_blocking_times_out<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = DelayQueue::<D, &str>::new();
+    queue.insert("late", Duration::from_millis(200));
+
+    assert!(queue.next_expired_blocking(Duration::from_millis(50)).is_err());
+    assert_eq!(queue.len(), 1);
+}
+
+pub fn timeout<D>()
+where
+    D: Delay,
+{
+    let result = blocking::timeout::<D, _, _>(Duration::from_millis(100), || 1 + 1);
+    assert_eq!(result.unwrap(), 2);
+}
+
+pub fn timeout_elapsed<D>()
+where
+    D: Delay,
+{
+    let start = D::Instant::now();
+    let result = blocking::timeout::<D, _, _>(Duration::from_millis(50), || {
+        std::thread::sleep(Duration::from_millis(200));
+    });
+    assert!(result.is_err());
+    assert!(D::Instant::now() - start < Duration::from_millis(100));
+}
+
+pub fn timeout_panic<D>()
+where
+    D: Delay,
+{
+    let _ = blocking::timeout::<D, _, ()>(Duration::from_secs(1), || panic!("boom"));
+}
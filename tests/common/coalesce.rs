@@ -0,0 +1,135 @@
+#![allow(dead_code, unused_imports)]
+
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use timer_kit::{Coalescer, Delay, DelayQueue, Instant};
+
+use super::*;
+
+pub async fn overlapping_sleeps_share_a_delay<D>()
+where
+    D: Delay,
+    D::Value: Clone + Send + Sync + 'static,
+    D::Instant: Unpin,
+{
+    let coalescer = Coalescer::<D>::new(Duration::from_millis(50));
+    let start = D::Instant::now();
+
+    // The first sleep fires at 150ms, which is within the window of the second one
+    let first = coalescer.sleep(Duration::from_millis(100));
+    let second = coalescer.sleep(Duration::from_millis(120));
+    futures::join!(first, second);
+
+    let elapsed = D::Instant::now() - start;
+    assert!(elapsed >= Duration::from_millis(120));
+    assert!(elapsed < Duration::from_millis(170));
+    assert_eq!(coalescer.armed_timers(), 2);
+    assert_eq!(coalescer.armed_delays(), 1);
+}
+
+pub async fn disjoint_sleeps_use_separate_delays<D>()
+where
+    D: Delay,
+    D::Value: Clone + Send + Sync + 'static,
+    D::Instant: Unpin,
+{
+    let coalescer = Coalescer::<D>::new(Duration::from_millis(10));
+
+    let first = coalescer.sleep(Duration::from_millis(50));
+    let second = coalescer.sleep(Duration::from_millis(150));
+    futures::join!(first, second);
+
+    assert_eq!(coalescer.armed_timers(), 2);
+    assert_eq!(coalescer.armed_delays(), 2);
+}
+
+pub async fn dropped_sleep_leaves_its_group<D>()
+where
+    D: Delay,
+    D::Value: Clone + Send + Sync + 'static,
+    D::Instant: Unpin,
+{
+    let coalescer = Coalescer::<D>::new(Duration::from_millis(50));
+    let start = D::Instant::now();
+
+    let mut first = coalescer.sleep(Duration::from_millis(100));
+    let mut second = coalescer.sleep(Duration::from_millis(120));
+    assert_pending!(&mut first);
+    assert_pending!(&mut second);
+
+    // The group is still driven by its delay without the timer that created it
+    drop(first);
+    second.await;
+
+    let elapsed = D::Instant::now() - start;
+    assert!(elapsed >= Duration::from_millis(120));
+    assert!(elapsed < Duration::from_millis(170));
+    assert_eq!(coalescer.armed_delays(), 1);
+}
+
+pub async fn interval_joins_other_timers<D>()
+where
+    D: Delay,
+    D::Value: Clone + Send + Sync + 'static,
+    D::Instant: Unpin,
+{
+    let coalescer = Coalescer::<D>::new(Duration::from_millis(30));
+    let start = D::Instant::now();
+
+    let mut interval = coalescer.interval(Duration::from_millis(100));
+    let sleep = coalescer.sleep(Duration::from_millis(110));
+    futures::join!(interval.tick(), sleep);
+    assert_eq!(coalescer.armed_timers(), 2);
+    assert_eq!(coalescer.armed_delays(), 1);
+
+    // The next tick is still scheduled from the first one
+    interval.tick().await;
+    let elapsed = D::Instant::now() - start;
+    assert!(elapsed >= Duration::from_millis(200));
+    assert!(elapsed < Duration::from_millis(250));
+    assert_eq!(coalescer.armed_delays(), 2);
+}
+
+pub async fn delay_queue_joins_other_timers<D>()
+where
+    D: Delay,
+    D::Value: Clone + Send + Sync + 'static,
+    D::Instant: Unpin,
+{
+    let coalescer = Coalescer::<D>::new(Duration::from_millis(50));
+
+    let mut queue = coalescer.delay_queue();
+    queue.insert("foo", Duration::from_millis(100));
+    let sleep = coalescer.sleep(Duration::from_millis(120));
+
+    let (expired, _) = futures::join!(queue.next(), sleep);
+    assert_eq!(*expired.unwrap().get_ref(), "foo");
+    assert_eq!(coalescer.armed_delays(), 1);
+}
+
+pub async fn reset_within_slack_keeps_the_delay<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let start = D::Instant::now();
+    let mut sleep = timer_kit::sleep::<D>(Duration::from_millis(100));
+    sleep.set_slack(Duration::from_millis(50));
+
+    // The delay is armed within the slack after the new deadline
+    sleep.reset(start + Duration::from_millis(80));
+    assert!(sleep.deadline() == start + Duration::from_millis(80));
+    (&mut sleep).await;
+
+    let elapsed = D::Instant::now() - start;
+    assert!(elapsed >= Duration::from_millis(100));
+    assert!(elapsed < Duration::from_millis(110));
+
+    // The delay is re-armed once it has elapsed
+    sleep.reset_after(Duration::from_millis(20));
+    sleep.await;
+    let elapsed = D::Instant::now() - start;
+    assert!(elapsed >= Duration::from_millis(120));
+    assert!(elapsed < Duration::from_millis(130));
+}
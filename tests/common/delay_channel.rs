@@ -0,0 +1,100 @@
+#![allow(unused_imports)]
+
+use std::time::Duration;
+
+use futures::StreamExt;
+use timer_kit::{delay_channel, error::DelayQueueError, Delay, Instant};
+
+use super::*;
+
+pub async fn send_and_receive<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let (sender, mut receiver) = delay_channel::<D, _>();
+    let start = D::Instant::now();
+    sender.send_after("second", Duration::from_millis(30)).unwrap();
+    sender.send_at("first", start + Duration::from_millis(10)).unwrap();
+    assert_eq!(receiver.len(), 2);
+    assert_pending!(receiver.next());
+
+    assert_eq!(receiver.recv().await, Some("first"));
+    assert!(D::Instant::now() - start >= Duration::from_millis(10));
+    assert_eq!(receiver.recv().await, Some("second"));
+    assert!(D::Instant::now() - start >= Duration::from_millis(30));
+    assert!(receiver.is_empty());
+
+    drop(sender);
+    assert_eq!(receiver.recv().await, None);
+}
+
+pub async fn cancel_message<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let (sender, mut receiver) = delay_channel::<D, _>();
+    let first = sender.send_after("foo", Duration::from_millis(10)).unwrap();
+    let second = sender.send_after("bar", Duration::from_millis(20)).unwrap();
+    // Dropping a handle keeps its message scheduled
+    drop(second);
+
+    assert_eq!(first.cancel(), Some("foo"));
+    drop(sender);
+
+    let messages: Vec<_> = receiver.by_ref().collect().await;
+    assert_eq!(messages, vec!["bar"]);
+}
+
+pub async fn cancel_after_receive<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let (sender, mut receiver) = delay_channel::<D, _>();
+    let handle = sender.send_after("foo", Duration::from_millis(10)).unwrap();
+    assert_eq!(receiver.recv().await, Some("foo"));
+
+    // The slot of the received message is reused by the next one
+    sender.send_after("bar", Duration::from_millis(10)).unwrap();
+    assert_eq!(handle.cancel(), None);
+    assert_eq!(receiver.recv().await, Some("bar"));
+}
+
+pub async fn send_from_clones<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let (sender, receiver) = delay_channel::<D, _>();
+    let sender2 = sender.clone();
+
+    let producer = async move {
+        sender.send_after(1, Duration::from_millis(20)).unwrap();
+        drop(sender);
+        timer_kit::sleep::<D>(Duration::from_millis(10)).await;
+        sender2.send_after(2, Duration::from_millis(20)).unwrap();
+    };
+    let ((), messages) = futures::join!(producer, receiver.collect::<Vec<_>>());
+    assert_eq!(messages, vec![1, 2]);
+}
+
+pub async fn send_after_receiver_dropped<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let (sender, receiver) = delay_channel::<D, _>();
+    let handle = sender.send_after("foo", Duration::from_millis(10)).unwrap();
+    assert!(!sender.is_closed());
+
+    drop(receiver);
+    assert!(sender.is_closed());
+    // Messages that were never received can still be taken back
+    assert_eq!(handle.cancel(), Some("foo"));
+
+    let err = sender.send_after("bar", Duration::from_millis(10)).unwrap_err();
+    assert_eq!(err.error(), DelayQueueError::Closed);
+    assert_eq!(err.into_inner(), "bar");
+}
@@ -0,0 +1,92 @@
+#![allow(unused_imports)]
+
+use std::time::Duration;
+
+use futures::StreamExt;
+use timer_kit::{Delay, DelayMap, Instant};
+
+use super::*;
+
+pub async fn insert_and_expire<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut map = DelayMap::<D, String, u32>::new();
+    let start = D::Instant::now();
+    map.insert("a".to_string(), 1, Duration::from_millis(10));
+    map.insert("b".to_string(), 2, Duration::from_millis(20));
+    assert_eq!(map.len(), 2);
+    assert!(map.contains_key("a"));
+    assert_eq!(map.get("b"), Some(&2));
+
+    assert_eq!(map.next().await, Some(("a".to_string(), 1)));
+    assert!(!map.contains_key("a"));
+    assert_eq!(map.next().await, Some(("b".to_string(), 2)));
+    assert!(D::Instant::now() - start >= Duration::from_millis(20));
+
+    assert!(map.is_empty());
+    assert_ready_none!(map.next());
+}
+
+pub async fn insert_replaces_existing<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut map = DelayMap::<D, u32, &str>::new();
+    let start = D::Instant::now();
+    assert_eq!(map.insert(1, "foo", Duration::from_millis(10)), None);
+    assert_eq!(map.insert(1, "bar", Duration::from_millis(50)), Some("foo"));
+    assert_eq!(map.len(), 1);
+    assert!(map.deadline(&1).unwrap() >= start + Duration::from_millis(50));
+
+    timer_kit::sleep::<D>(Duration::from_millis(20)).await;
+    assert_pending!(map.next());
+
+    assert_eq!(map.next().await, Some((1, "bar")));
+    assert!(D::Instant::now() - start >= Duration::from_millis(50));
+    assert_ready_none!(map.next());
+}
+
+pub async fn remove_and_reset<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut map = DelayMap::<D, u32, &str>::new();
+    let start = D::Instant::now();
+    map.insert(1, "foo", Duration::from_millis(10));
+    map.insert(2, "bar", Duration::from_millis(20));
+
+    assert_eq!(map.remove(&1), Some("foo"));
+    assert_eq!(map.remove(&1), None);
+    assert!(!map.reset(&1, Duration::from_millis(10)));
+
+    assert!(map.reset(&2, Duration::from_millis(50)));
+    *map.get_mut(&2).unwrap() = "baz";
+
+    assert_eq!(map.next().await, Some((2, "baz")));
+    assert!(D::Instant::now() - start >= Duration::from_millis(50));
+    assert!(map.is_empty());
+}
+
+pub async fn reinsert_after_expiry<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut map = DelayMap::<D, u32, u32>::new();
+    map.insert(1, 1, Duration::from_millis(10));
+    assert_eq!(map.next().await, Some((1, 1)));
+
+    assert_eq!(map.insert(1, 2, Duration::from_millis(10)), None);
+    let items: Vec<_> = map.iter().map(|(k, v, _)| (*k, *v)).collect();
+    assert_eq!(items, vec![(1, 2)]);
+    assert_eq!(map.next().await, Some((1, 2)));
+
+    map.insert(1, 3, Duration::from_millis(10));
+    map.clear();
+    assert!(!map.contains_key(&1));
+    assert_ready_none!(map.next());
+}
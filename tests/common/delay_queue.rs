@@ -755,3 +755,620 @@ where
     // This should panic
     queue.remove(&key1);
 }
+
+pub async fn try_insert_far_deadline_rejected<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    queue.set_far_deadline_behavior(timer_kit::FarDeadlineBehavior::Reject);
+    let far = D::Instant::now() + Duration::from_secs(3 * 365 * 24 * 60 * 60);
+
+    let result = queue.try_insert_at("foo", far);
+    assert_eq!(result, Err(timer_kit::error::DelayQueueError::DeadlineOutOfRange));
+    assert!(queue.is_empty());
+    assert_ready_none!(queue.next());
+}
+
+pub async fn try_insert_far_deadline_clamped<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    queue.set_far_deadline_behavior(timer_kit::FarDeadlineBehavior::Clamp);
+    let start = D::Instant::now();
+    let far = start + Duration::from_secs(3 * 365 * 24 * 60 * 60);
+
+    let key = queue.try_insert_at("foo", far).unwrap();
+    assert_pending!(queue.next());
+
+    let entry = queue.remove(&key);
+    assert!(entry.deadline() < far);
+    assert!(entry.deadline() > start + Duration::from_secs(365 * 24 * 60 * 60));
+}
+
+pub async fn try_reset_invalid_key<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    let key = queue.insert("foo", Duration::from_millis(100));
+    queue.remove(&key);
+
+    let result = queue.try_reset(&key, Duration::from_millis(100));
+    assert_eq!(result, Err(timer_kit::error::DelayQueueError::InvalidKey));
+}
+
+pub async fn panic_on_insert_far_deadline<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    queue.set_far_deadline_behavior(timer_kit::FarDeadlineBehavior::Reject);
+
+    // This should panic
+    queue.insert("foo", Duration::from_secs(3 * 365 * 24 * 60 * 60));
+}
+
+pub async fn far_deadline_overflow<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    let start = D::Instant::now();
+    let far = start + Duration::from_secs(10 * 365 * 24 * 60 * 60);
+
+    let far_key = queue.insert_at("far", far);
+    queue.insert_at("near", start + Duration::from_millis(50));
+
+    let entry = queue.next().await.unwrap();
+    assert_eq!(entry.into_inner(), "near");
+    assert_pending!(queue.next());
+
+    let entry = queue.remove(&far_key);
+    assert!(entry.deadline() >= far);
+    assert!(entry.deadline() - far < Duration::from_millis(1));
+    assert_eq!(entry.into_inner(), "far");
+    assert_ready_none!(queue.next());
+}
+
+pub async fn coarse_resolution<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let start = D::Instant::now();
+    let mut queue: timer_kit::DelayQueue<D, _> = timer_kit::DelayQueue::builder()
+        .resolution(Duration::from_millis(100))
+        .build();
+    assert_eq!(queue.resolution(), Duration::from_millis(100));
+
+    let key = queue.insert_at("foo", start + Duration::from_millis(10));
+
+    // Deadlines are rounded up to the next tick
+    let deadline = queue.remove(&key).deadline();
+    assert!(deadline >= start + Duration::from_millis(100));
+    assert!(deadline < start + Duration::from_millis(200));
+
+    queue.insert_at("foo", start + Duration::from_millis(10));
+    timer_kit::sleep::<D>(Duration::from_millis(50)).await;
+    assert_pending!(queue.next());
+
+    let entry = queue.next().await.unwrap();
+    let elapsed = D::Instant::now() - start;
+    assert!(elapsed >= Duration::from_millis(100));
+    assert_eq!(entry.into_inner(), "foo");
+}
+
+pub async fn fine_resolution<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let start = D::Instant::now();
+    let mut queue: timer_kit::DelayQueue<D, _> = timer_kit::DelayQueue::builder()
+        .resolution(Duration::from_micros(1))
+        .build();
+
+    let key = queue.insert_at("foo", start + Duration::from_micros(1500));
+
+    let deadline = queue.remove(&key).deadline();
+    assert!(deadline >= start + Duration::from_micros(1500));
+    assert!(deadline < start + Duration::from_micros(1502));
+
+    queue.insert_at("one", start + Duration::from_micros(20_100));
+    queue.insert_at("two", start + Duration::from_micros(20_900));
+
+    assert_eq!(queue.next().await.unwrap().into_inner(), "one");
+    assert_eq!(queue.next().await.unwrap().into_inner(), "two");
+}
+
+pub async fn get_and_get_mut<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    let key = queue.insert(String::from("foo"), Duration::from_millis(10));
+    assert!(queue.contains(&key));
+    assert_eq!(queue.get(&key).map(String::as_str), Some("foo"));
+
+    queue.get_mut(&key).unwrap().push_str("bar");
+    assert_eq!(queue.get(&key).map(String::as_str), Some("foobar"));
+
+    // The entry is still scheduled
+    let entry = queue.next().await.unwrap();
+    assert_eq!(entry.into_inner(), "foobar");
+    assert!(!queue.contains(&key));
+    assert!(queue.get(&key).is_none());
+    assert!(queue.get_mut(&key).is_none());
+    assert!(queue.deadline(&key).is_none());
+}
+
+pub async fn deadline_of_key<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    let start = D::Instant::now();
+    let key = queue.insert_at("foo", start + Duration::from_millis(100));
+
+    let deadline = queue.deadline(&key).unwrap();
+    assert!(deadline >= start + Duration::from_millis(100));
+    assert!(deadline < start + Duration::from_millis(101));
+
+    queue.reset_at(&key, start + Duration::from_millis(200));
+    let deadline = queue.deadline(&key).unwrap();
+    assert!(deadline >= start + Duration::from_millis(200));
+    assert!(queue.remove(&key).deadline() == deadline);
+}
+
+pub async fn peek_next_deadline<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    assert!(queue.peek_next_deadline().is_none());
+
+    let start = D::Instant::now();
+    let far = queue.insert_at("far", start + Duration::from_secs(3 * 365 * 86400));
+    assert!(queue.peek_next_deadline() == queue.deadline(&far));
+
+    // Both entries are in the same slot of the wheel
+    let late = queue.insert_at("late", start + Duration::from_millis(100));
+    let early = queue.insert_at("early", start + Duration::from_millis(90));
+    queue.insert_at("later", start + Duration::from_secs(60));
+    assert!(queue.peek_next_deadline() == queue.deadline(&early));
+
+    queue.remove(&early);
+    assert!(queue.peek_next_deadline() == queue.deadline(&late));
+
+    let entry = queue.next().await.unwrap();
+    assert_eq!(entry.into_inner(), "late");
+    assert!(queue.peek_next_deadline().unwrap() >= start + Duration::from_secs(60));
+}
+
+pub async fn peek_next_deadline_expired<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    let start = D::Instant::now();
+    queue.insert_at("foo", start + Duration::from_millis(10));
+
+    timer_kit::sleep::<D>(Duration::from_millis(20)).await;
+
+    // Already expired, but not yet polled
+    let deadline = queue.peek_next_deadline().unwrap();
+    assert!(deadline <= D::Instant::now());
+
+    // Inserting in the past puts the entry in the expired queue
+    let key = queue.insert_at("bar", start);
+    let entry = queue.next().await.unwrap();
+    assert_eq!(entry.key(), key);
+    assert!(queue.peek_next_deadline() == Some(deadline));
+}
+
+pub async fn iter_and_iter_mut<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    let keys: Vec<_> = (0..5)
+        .map(|i| queue.insert(i, Duration::from_millis(10 * (i as u64 + 1))))
+        .collect();
+    queue.remove(&keys[1]);
+    queue.compact();
+
+    assert_eq!(queue.iter().len(), 4);
+    for (key, item, deadline) in queue.iter() {
+        assert_eq!(key, keys[*item]);
+        assert_eq!(queue.get(&key), Some(item));
+        assert!(queue.deadline(&key) == Some(deadline));
+    }
+
+    for (_, item, _) in queue.iter_mut() {
+        *item *= 10;
+    }
+
+    let mut items: Vec<_> = queue.iter().map(|(_, item, _)| *item).collect();
+    items.sort();
+    assert_eq!(items, vec![0, 20, 30, 40]);
+
+    let entry = queue.next().await.unwrap();
+    assert_eq!(entry.into_inner(), 0);
+}
+
+pub async fn stale_key_is_invalid<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    let stale = queue.insert("foo", Duration::from_millis(10));
+    queue.remove(&stale);
+
+    // The new entry reuses the slot of the removed one
+    let key = queue.insert("bar", Duration::from_millis(10));
+    assert_ne!(key, stale);
+    assert!(!queue.contains(&stale));
+    assert!(queue.get(&stale).is_none());
+    assert_eq!(
+        queue.try_remove(&stale).map(|expired| expired.into_inner()),
+        Err(timer_kit::error::DelayQueueError::InvalidKey)
+    );
+    assert_eq!(
+        queue.try_reset(&stale, Duration::from_millis(20)),
+        Err(timer_kit::error::DelayQueueError::InvalidKey)
+    );
+    assert_eq!(queue.get(&key), Some(&"bar"));
+
+    // Keys of expired entries become stale as well
+    let entry = queue.next().await.unwrap();
+    assert_eq!(entry.key(), key);
+    let key2 = queue.insert("baz", Duration::from_millis(10));
+    assert!(queue.try_remove(&key).is_err());
+
+    // Keys handed out before a `clear` are stale
+    queue.clear();
+    let key3 = queue.insert("qux", Duration::from_millis(10));
+    assert!(!queue.contains(&key2));
+    assert!(queue.contains(&key3));
+}
+
+pub async fn stale_key_after_compact<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    let key0 = queue.insert("foo", Duration::from_millis(10));
+    let key1 = queue.insert("bar", Duration::from_millis(10));
+    queue.remove(&key0);
+    queue.compact();
+
+    let key2 = queue.insert("baz", Duration::from_millis(10));
+    assert!(queue.try_remove(&key0).is_err());
+    assert_eq!(queue.get(&key1), Some(&"bar"));
+    assert_eq!(queue.get(&key2), Some(&"baz"));
+
+    queue.remove(&key1);
+    assert!(queue.try_remove(&key1).is_err());
+    assert_eq!(queue.remove(&key2).into_inner(), "baz");
+}
+
+pub async fn panic_on_remove_of_stale_key<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    let stale = queue.insert("foo", Duration::from_millis(10));
+    queue.remove(&stale);
+    queue.insert("bar", Duration::from_millis(10));
+
+    queue.remove(&stale);
+}
+
+pub async fn panic_on_reset_of_stale_key<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    let stale = queue.insert("foo", Duration::from_millis(10));
+    queue.remove(&stale);
+    queue.insert("bar", Duration::from_millis(10));
+
+    queue.reset(&stale, Duration::from_millis(20));
+}
+
+pub async fn poll_expired_batch<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    use futures::future::poll_fn;
+
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    let start = D::Instant::now();
+    for i in 0..10 {
+        queue.insert_at(i, start + Duration::from_millis(10));
+    }
+    queue.insert_at(10, start + Duration::from_millis(50));
+
+    let mut buf = Vec::new();
+    assert_pending!(poll_fn(|cx| queue.poll_expired_batch(cx, &mut buf, 100)));
+    assert!(buf.is_empty());
+
+    timer_kit::sleep::<D>(Duration::from_millis(20)).await;
+
+    let n = poll_fn(|cx| queue.poll_expired_batch(cx, &mut buf, 4)).await;
+    assert_eq!(n, 4);
+    let n = poll_fn(|cx| queue.poll_expired_batch(cx, &mut buf, 100)).await;
+    assert_eq!(n, 6);
+    let mut items: Vec<_> = buf.drain(..).map(|e| e.into_inner()).collect();
+    items.sort();
+    assert_eq!(items, (0..10).collect::<Vec<_>>());
+
+    assert_pending!(poll_fn(|cx| queue.poll_expired_batch(cx, &mut buf, 100)));
+    let n = poll_fn(|cx| queue.poll_expired_batch(cx, &mut buf, 100)).await;
+    assert_eq!(n, 1);
+    assert_eq!(buf.pop().unwrap().into_inner(), 10);
+    assert!(D::Instant::now() - start >= Duration::from_millis(50));
+
+    let n = poll_fn(|cx| queue.poll_expired_batch(cx, &mut buf, 100)).await;
+    assert_eq!(n, 0);
+}
+
+pub async fn drain_expired<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    let start = D::Instant::now();
+    queue.insert_at("b", start + Duration::from_millis(20));
+    queue.insert_at("a", start + Duration::from_millis(10));
+    queue.insert_at("c", start + Duration::from_millis(500));
+    queue.insert_at("past", start);
+
+    timer_kit::sleep::<D>(Duration::from_millis(30)).await;
+
+    let items: Vec<_> = queue
+        .drain_expired()
+        .into_iter()
+        .map(|e| e.into_inner())
+        .collect();
+    assert_eq!(items, vec!["past", "a", "b"]);
+    assert!(queue.drain_expired().is_empty());
+    assert_eq!(queue.len(), 1);
+
+    // The delay is updated to the remaining entry
+    assert_pending!(queue.next());
+    let entry = queue.next().await.unwrap();
+    assert_eq!(entry.into_inner(), "c");
+    assert!(D::Instant::now() - start >= Duration::from_millis(500));
+}
+
+pub async fn retain<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    let start = D::Instant::now();
+    let keys: Vec<_> = (0..10)
+        .map(|i| queue.insert_at(i, start + Duration::from_millis(10 * (i as u64 + 1))))
+        .collect();
+    // Already expired entries can be retained or removed as well
+    queue.insert_at(100, start);
+    queue.insert_at(101, start);
+
+    queue.retain(|key, value, deadline| {
+        assert!(deadline >= start);
+        if *value >= 100 {
+            return *value == 101;
+        }
+        assert_eq!(key, keys[*value]);
+        *value *= 10;
+        *value % 20 != 0
+    });
+    assert_eq!(queue.len(), 6);
+    assert!(!queue.contains(&keys[0]));
+    assert_eq!(queue.get(&keys[1]), Some(&10));
+
+    let mut items = vec![];
+    while let Some(entry) = queue.next().await {
+        items.push(entry.into_inner());
+    }
+    assert_eq!(items, vec![101, 10, 30, 50, 70, 90]);
+}
+
+pub async fn retain_updates_delay<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    let start = D::Instant::now();
+    queue.insert_at("early", start + Duration::from_millis(10));
+    queue.insert_at("late", start + Duration::from_millis(50));
+    assert_pending!(queue.next());
+
+    queue.retain(|_, value, _| *value == "late");
+    timer_kit::sleep::<D>(Duration::from_millis(20)).await;
+    assert_pending!(queue.next());
+
+    assert_eq!(queue.next().await.unwrap().into_inner(), "late");
+    assert!(D::Instant::now() - start >= Duration::from_millis(50));
+
+    queue.insert("foo", Duration::from_millis(10));
+    queue.retain(|_, _, _| false);
+    assert!(queue.is_empty());
+    assert_ready_none!(queue.next());
+}
+
+pub async fn extend_and_from_iter<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue: timer_kit::DelayQueue<D, _> = (0..5)
+        .map(|i| (i, Duration::from_millis(50 - 10 * i as u64)))
+        .collect();
+    assert_eq!(queue.len(), 5);
+
+    queue.extend(vec![(10, Duration::from_millis(5))]);
+
+    let start = D::Instant::now();
+    queue.extend_at((20..22).map(|i| (i, start)));
+    assert_eq!(queue.len(), 8);
+
+    let mut items = vec![];
+    while let Some(entry) = queue.next().await {
+        items.push(entry.into_inner());
+    }
+    assert!(items[..2].contains(&20) && items[..2].contains(&21));
+    assert_eq!(&items[2..], &[10, 4, 3, 2, 1, 0]);
+}
+
+pub async fn extend_after_poll<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    let start = D::Instant::now();
+    queue.insert_at("late", start + Duration::from_millis(100));
+    assert_pending!(queue.next());
+
+    // The bulk insertion moves the delay earlier
+    queue.extend_at(vec![
+        ("b", start + Duration::from_millis(20)),
+        ("a", start + Duration::from_millis(10)),
+    ]);
+
+    assert_eq!(queue.next().await.unwrap().into_inner(), "a");
+    assert_eq!(queue.next().await.unwrap().into_inner(), "b");
+    assert!(D::Instant::now() - start < Duration::from_millis(100));
+    assert_eq!(queue.next().await.unwrap().into_inner(), "late");
+}
+
+pub async fn panic_on_extend_far_deadline<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = timer_kit::DelayQueue::<D, _>::new();
+    queue.set_far_deadline_behavior(timer_kit::FarDeadlineBehavior::Reject);
+    queue.extend(vec![
+        ("near", Duration::from_millis(10)),
+        ("far", Duration::from_secs(3 * 365 * 86400)),
+    ]);
+}
+
+fn fifo_queue<D, T>() -> timer_kit::DelayQueue<D, T>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    timer_kit::DelayQueue::builder()
+        .expiration_order(timer_kit::ExpirationOrder::Fifo)
+        .build()
+}
+
+pub async fn fifo_equal_deadlines<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = fifo_queue::<D, _>();
+    assert_eq!(queue.expiration_order(), timer_kit::ExpirationOrder::Fifo);
+
+    let when = D::Instant::now() + Duration::from_millis(10);
+    for i in 0..100 {
+        queue.insert_at(i, when);
+    }
+
+    let mut buf = Vec::new();
+    while futures::future::poll_fn(|cx| queue.poll_expired_batch(cx, &mut buf, 7)).await > 0 {}
+    let items: Vec<_> = buf.into_iter().map(|e| e.into_inner()).collect();
+    assert_eq!(items, (0..100).collect::<Vec<_>>());
+}
+
+pub async fn fifo_expired_entries<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = fifo_queue::<D, _>();
+    let start = D::Instant::now();
+    for i in 0..10 {
+        queue.insert_at(i, start);
+    }
+
+    let mut items = vec![];
+    while let Some(entry) = queue.next().await {
+        items.push(entry.into_inner());
+    }
+    assert_eq!(items, (0..10).collect::<Vec<_>>());
+}
+
+pub async fn fifo_across_levels<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = fifo_queue::<D, _>();
+    let start = D::Instant::now();
+    let when = start + Duration::from_millis(200);
+
+    // Stored in a higher level of the wheel, and cascaded down to the lowest
+    // level when the queue advances to "tick"
+    queue.insert_at("first", when);
+    queue.insert_at("tick", start + Duration::from_millis(197));
+    assert_eq!(queue.next().await.unwrap().into_inner(), "tick");
+
+    // Stored directly in the lowest level
+    queue.insert_at("second", when);
+    let key = queue.insert_at("third", start);
+    queue.reset_at(&key, when);
+
+    assert_eq!(queue.next().await.unwrap().into_inner(), "first");
+    assert_eq!(queue.next().await.unwrap().into_inner(), "second");
+    assert_eq!(queue.next().await.unwrap().into_inner(), "third");
+}
+
+pub async fn fifo_with_priority<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = fifo_queue::<D, _>();
+    let start = D::Instant::now();
+    let when = start + Duration::from_millis(10);
+    queue.insert_at("low 1", when);
+    queue.insert_at_with_priority("high 1", when, 2);
+    queue.insert_at_with_priority("mid", when, 1);
+    queue.insert_at("low 2", when);
+    queue.insert_at_with_priority("high 2", when, 2);
+    queue.insert_with_priority("later", Duration::from_millis(50), 10);
+    let key = queue.insert_at_with_priority("removed", when, 2);
+    queue.remove(&key);
+
+    let mut items = vec![];
+    while let Some(entry) = queue.next().await {
+        items.push(entry.into_inner());
+    }
+    assert_eq!(items, vec!["high 1", "high 2", "mid", "low 1", "low 2", "later"]);
+}
@@ -0,0 +1,151 @@
+#![allow(dead_code, unused_imports)]
+
+use std::time::Duration;
+
+use timer_kit::{Delay, Instant, TimerDriver};
+
+use super::*;
+
+pub async fn many_sleeps_share_the_driver<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let driver = TimerDriver::<D>::new();
+    let run = driver.run();
+
+    futures::join!(run, async move {
+        let start = D::Instant::now();
+        let sleeps = (0..1000u64).map(|i| driver.sleep(Duration::from_millis(50 + i % 50)));
+        futures::future::join_all(sleeps).await;
+
+        let elapsed = D::Instant::now() - start;
+        assert!(elapsed >= Duration::from_millis(99));
+        assert!(elapsed < Duration::from_millis(115));
+        assert!(driver.is_empty());
+    });
+}
+
+pub async fn sleep_completes_at_its_deadline<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let driver = TimerDriver::<D>::new();
+    let run = driver.run();
+
+    futures::join!(run, async move {
+        let deadline = D::Instant::now() + Duration::from_millis(100);
+        let sleep = driver.sleep_until(deadline);
+        assert!(sleep.deadline() == deadline);
+        sleep.await;
+
+        let now = D::Instant::now();
+        assert!(now >= deadline);
+        assert!(now - deadline < Duration::from_millis(15));
+    });
+}
+
+pub async fn earlier_sleep_rearms_the_delay<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let driver = TimerDriver::<D>::new();
+    let run = driver.run();
+
+    futures::join!(run, async move {
+        let start = D::Instant::now();
+        let mut late = driver.sleep(Duration::from_millis(300));
+        assert_pending!(&mut late);
+        driver.sleep(Duration::from_millis(20)).await;
+
+        // The driver is armed for 300ms, and must wake up earlier
+        driver.sleep(Duration::from_millis(50)).await;
+        let elapsed = D::Instant::now() - start;
+        assert!(elapsed >= Duration::from_millis(70));
+        assert!(elapsed < Duration::from_millis(95));
+        assert_eq!(driver.len(), 1);
+    });
+}
+
+pub async fn dropped_sleep_is_cancelled<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let driver = TimerDriver::<D>::new();
+
+    let mut sleep = driver.sleep(Duration::from_millis(100));
+    assert_pending!(&mut sleep);
+    assert_eq!(driver.len(), 1);
+
+    drop(sleep);
+    assert!(driver.is_empty());
+}
+
+pub async fn reset<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let driver = TimerDriver::<D>::new();
+    let run = driver.run();
+
+    futures::join!(run, async move {
+        let start = D::Instant::now();
+        let mut sleep = driver.sleep(Duration::from_millis(300));
+        assert_pending!(&mut sleep);
+
+        sleep.reset(start + Duration::from_millis(100));
+        assert!(sleep.deadline() == start + Duration::from_millis(100));
+        assert_eq!(driver.len(), 1);
+        (&mut sleep).await;
+
+        let elapsed = D::Instant::now() - start;
+        assert!(elapsed >= Duration::from_millis(100));
+        assert!(elapsed < Duration::from_millis(115));
+        assert!(sleep.is_elapsed());
+    });
+}
+
+pub async fn timeout<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let driver = TimerDriver::<D>::new();
+    let run = driver.run();
+
+    futures::join!(run, async move {
+        let result = driver.timeout(Duration::from_millis(100), async { 1 + 1 }).await;
+        assert_eq!(result.unwrap(), 2);
+        assert!(driver.is_empty());
+
+        let start = D::Instant::now();
+        let result = driver.timeout(Duration::from_millis(100), never()).await;
+        assert!(result.is_err());
+
+        let elapsed = D::Instant::now() - start;
+        assert!(elapsed >= Duration::from_millis(100));
+        assert!(elapsed < Duration::from_millis(115));
+    });
+}
+
+pub async fn driver_completes_once_dropped<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let driver = TimerDriver::<D>::new();
+    let mut run = driver.run();
+    assert_pending!(&mut run);
+
+    let sleep = driver.sleep(Duration::from_millis(100));
+    drop(driver);
+    assert_pending!(&mut run);
+
+    // The sleeps keep the driver alive
+    drop(sleep);
+    assert_ready!(&mut run);
+}
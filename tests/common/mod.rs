@@ -109,4 +109,27 @@ impl std::future::Future for Never {
 pub mod interval;
 pub mod sleep;
 pub mod timeout;
-pub mod delay_queue;
\ No newline at end of file
+pub mod delay_queue;
+
+#[cfg(feature = "std")]
+pub mod blocking;
+#[cfg(feature = "std")]
+pub mod coalesce;
+#[cfg(feature = "std")]
+pub mod delay_map;
+#[cfg(feature = "std")]
+pub mod driver;
+#[cfg(feature = "std")]
+pub mod shared_delay_queue;
+#[cfg(feature = "std")]
+pub mod delay_channel;
+#[cfg(feature = "std")]
+pub mod ttl_cache;
+#[cfg(feature = "std")]
+pub mod watchdog;
+
+#[cfg(feature = "serde")]
+pub mod snapshot;
+
+#[cfg(all(feature = "std", feature = "tracing"))]
+pub mod tracing;
@@ -0,0 +1,148 @@
+#![allow(unused_imports)]
+
+use std::time::Duration;
+
+use futures::StreamExt;
+use timer_kit::{error::DelayQueueError, Delay, Instant, SharedDelayQueue};
+
+use super::*;
+
+pub async fn insert_from_task<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = SharedDelayQueue::<D, _>::new();
+    let inserter = queue.inserter();
+
+    let producer = async move {
+        timer_kit::sleep::<D>(Duration::from_millis(10)).await;
+        inserter.insert("foo", Duration::from_millis(10)).unwrap();
+        timer_kit::sleep::<D>(Duration::from_millis(10)).await;
+        inserter.insert("bar", Duration::from_millis(20)).unwrap();
+    };
+    let consumer = async {
+        let mut items = vec![];
+        while let Some(entry) = queue.next().await {
+            items.push(entry.into_inner());
+        }
+        items
+    };
+
+    let ((), items) = futures::join!(producer, consumer);
+    assert_eq!(items, vec!["foo", "bar"]);
+}
+
+pub async fn earlier_insert_wakes_consumer<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = SharedDelayQueue::<D, _>::new();
+    let inserter = queue.inserter();
+    let start = D::Instant::now();
+    inserter.insert_at("late", start + Duration::from_millis(500)).unwrap();
+
+    let producer = async {
+        timer_kit::sleep::<D>(Duration::from_millis(10)).await;
+        inserter.insert_at("early", start + Duration::from_millis(20)).unwrap();
+    };
+    let consumer = async { queue.next().await.unwrap() };
+
+    let ((), entry) = futures::join!(producer, consumer);
+    assert_eq!(entry.into_inner(), "early");
+    assert!(D::Instant::now() - start < Duration::from_millis(500));
+    assert_eq!(queue.len(), 1);
+}
+
+pub async fn remove_and_reset<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = SharedDelayQueue::<D, _>::new();
+    let inserter = queue.inserter();
+    let start = D::Instant::now();
+    let first = inserter.insert_at("foo", start + Duration::from_millis(10)).unwrap();
+    let second = inserter.insert_at("bar", start + Duration::from_millis(200)).unwrap();
+    assert_pending!(queue.next());
+
+    assert_eq!(inserter.remove(&first).unwrap().into_inner(), "foo");
+    assert!(inserter.remove(&first).is_none());
+    assert_eq!(inserter.reset(&first, Duration::from_millis(10)), Err(DelayQueueError::InvalidKey));
+    inserter.reset_at(&second, start + Duration::from_millis(30)).unwrap();
+    drop(inserter);
+
+    assert_eq!(queue.next().await.unwrap().into_inner(), "bar");
+    let elapsed = D::Instant::now() - start;
+    assert!(elapsed >= Duration::from_millis(30));
+    assert!(elapsed < Duration::from_millis(200));
+    assert_ready_none!(queue.next());
+}
+
+pub async fn stream_ends_when_inserters_dropped<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = SharedDelayQueue::<D, u32>::new();
+    let inserter = queue.inserter();
+    let inserter2 = inserter.clone();
+
+    assert_pending!(queue.next());
+    drop(inserter);
+    assert_pending!(queue.next());
+
+    let producer = async move {
+        timer_kit::sleep::<D>(Duration::from_millis(10)).await;
+        drop(inserter2);
+    };
+    let ((), next) = futures::join!(producer, queue.next());
+    assert!(next.is_none());
+}
+
+pub async fn insert_after_consumer_dropped<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let queue = SharedDelayQueue::<D, _>::new();
+    let inserter = queue.inserter();
+    assert!(!inserter.is_closed());
+    let key = inserter.insert("foo", Duration::from_millis(10)).unwrap();
+
+    drop(queue);
+    assert!(inserter.is_closed());
+    assert_eq!(inserter.insert("bar", Duration::from_millis(10)), Err(DelayQueueError::Closed));
+    assert_eq!(inserter.reset(&key, Duration::from_millis(10)), Err(DelayQueueError::Closed));
+}
+
+pub async fn insert_from_thread<D>()
+where
+    D: Delay + Send + 'static,
+    D::Instant: Unpin + Send,
+{
+    let mut queue = SharedDelayQueue::<D, _>::new();
+    let start = D::Instant::now();
+
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let inserter = queue.inserter();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(10));
+                inserter.insert(i, Duration::from_millis(20 * i)).unwrap();
+            })
+        })
+        .collect();
+
+    let mut items = vec![];
+    while let Some(entry) = queue.next().await {
+        items.push(entry.into_inner());
+    }
+    assert_eq!(items, vec![0, 1, 2, 3]);
+    assert!(D::Instant::now() - start >= Duration::from_millis(70));
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
@@ -62,3 +62,70 @@ where
     timer_kit::sleep::<D>(Duration::from_millis(50)).await;
     assert_ready!(sleep);
 }
+
+pub async fn is_elapsed_and_remaining<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut sleep = timer_kit::sleep::<D>(Duration::from_millis(100));
+    assert!(!sleep.is_elapsed());
+    assert!(sleep.remaining() > Duration::from_millis(50));
+    assert!(sleep.remaining() <= Duration::from_millis(100));
+
+    (&mut sleep).await;
+    assert!(sleep.is_elapsed());
+    assert_eq!(sleep.remaining(), Duration::ZERO);
+    assert!(sleep.elapsed_since_creation() >= Duration::from_millis(100));
+}
+
+pub async fn reset_after<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut sleep = timer_kit::sleep::<D>(Duration::from_millis(10));
+    (&mut sleep).await;
+    assert!(sleep.is_elapsed());
+
+    // Re-arm the elapsed sleep
+    sleep.reset_after(Duration::from_millis(100));
+    assert!(!sleep.is_elapsed());
+    timer_kit::sleep::<D>(Duration::from_millis(50)).await;
+    assert_pending!(&mut sleep);
+
+    timer_kit::sleep::<D>(Duration::from_millis(60)).await;
+    assert_ready!(&mut sleep);
+    assert!(sleep.is_elapsed());
+}
+
+pub async fn never<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut sleep = timer_kit::Sleep::<D>::never();
+    assert!(!sleep.is_elapsed());
+    assert!(sleep.remaining() > Duration::from_secs(86400 * 365));
+
+    timer_kit::sleep::<D>(Duration::from_millis(50)).await;
+    assert_pending!(&mut sleep);
+
+    sleep.reset_after(Duration::from_millis(50));
+    timer_kit::sleep::<D>(Duration::from_millis(60)).await;
+    assert_ready!(&mut sleep);
+}
+
+pub async fn max_duration<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    // The deadline saturates instead of overflowing
+    let mut sleep = timer_kit::sleep::<D>(Duration::MAX);
+    assert!(sleep.remaining() > Duration::from_secs(86400 * 365));
+    assert_pending!(&mut sleep);
+
+    sleep.reset_after(Duration::from_millis(10));
+    (&mut sleep).await;
+}
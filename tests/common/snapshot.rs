@@ -0,0 +1,126 @@
+#![allow(unused_imports)]
+
+use std::time::Duration;
+
+use futures::StreamExt;
+use timer_kit::{Delay, DelayQueue, ExpirationOrder, Instant, RestorePolicy, Snapshot};
+
+use super::*;
+
+pub async fn snapshot_and_restore<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = DelayQueue::<D, String>::new();
+    queue.insert("bar".to_string(), Duration::from_millis(30));
+    queue.insert("foo".to_string(), Duration::from_millis(10));
+
+    let snapshot = queue.snapshot();
+    assert_eq!(snapshot.len(), 2);
+    assert_eq!(snapshot.entries()[0].value, "foo");
+    // Deadlines are rounded up to the resolution of the queue
+    assert!(snapshot.entries()[0].remaining <= Duration::from_millis(11));
+    assert!(snapshot.entries()[1].deadline > snapshot.taken_at());
+    // Taking a snapshot does not modify the queue
+    assert_eq!(queue.len(), 2);
+
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let snapshot: Snapshot<String> = serde_json::from_str(&json).unwrap();
+
+    let start = D::Instant::now();
+    let mut restored = DelayQueue::<D, String>::new();
+    assert_eq!(restored.restore(snapshot, RestorePolicy::FireImmediately), Ok(2));
+    assert_pending!(restored.next());
+
+    assert_eq!(restored.next().await.unwrap().into_inner(), "foo");
+    assert_eq!(restored.next().await.unwrap().into_inner(), "bar");
+    assert!(D::Instant::now() - start < Duration::from_millis(100));
+    assert_ready_none!(restored.next());
+}
+
+async fn overdue_snapshot<D>() -> Snapshot<&'static str>
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = DelayQueue::<D, _>::new();
+    queue.insert("overdue", Duration::from_millis(10));
+    queue.insert("pending", Duration::from_secs(60));
+    let snapshot = queue.snapshot();
+
+    // The process is down while the first deadline passes
+    timer_kit::sleep::<D>(Duration::from_millis(30)).await;
+    snapshot
+}
+
+pub async fn restore_fire_immediately<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let snapshot = overdue_snapshot::<D>().await;
+    let mut queue = DelayQueue::<D, _>::new();
+    assert_eq!(queue.restore(snapshot, RestorePolicy::FireImmediately), Ok(2));
+
+    let entry = queue.next().await.unwrap();
+    assert_eq!(*entry.get_ref(), "overdue");
+    assert_pending!(queue.next());
+    assert_eq!(queue.len(), 1);
+}
+
+pub async fn restore_drop<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let snapshot = overdue_snapshot::<D>().await;
+    let mut queue = DelayQueue::<D, _>::new();
+    assert_eq!(queue.restore(snapshot, RestorePolicy::Drop), Ok(1));
+
+    let (_, value, deadline) = queue.iter().next().unwrap();
+    assert_eq!(*value, "pending");
+    assert!(deadline - D::Instant::now() <= Duration::from_secs(60));
+    assert_pending!(queue.next());
+}
+
+pub async fn restore_shift<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let snapshot = overdue_snapshot::<D>().await;
+    let start = D::Instant::now();
+    let mut queue = DelayQueue::<D, _>::new();
+    assert_eq!(queue.restore(snapshot, RestorePolicy::Shift), Ok(2));
+    assert_pending!(queue.next());
+
+    assert_eq!(queue.next().await.unwrap().into_inner(), "overdue");
+    assert!(D::Instant::now() - start >= Duration::from_millis(5));
+    assert_eq!(queue.len(), 1);
+}
+
+pub async fn restore_keeps_fifo_order<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut queue = DelayQueue::<D, _>::builder()
+        .expiration_order(ExpirationOrder::Fifo)
+        .build();
+    let when = D::Instant::now() + Duration::from_millis(10);
+    queue.insert_at("low 1", when);
+    queue.insert_at_with_priority("high", when, 1);
+    queue.insert_at("low 2", when);
+
+    let snapshot = queue.snapshot();
+    let values: Vec<_> = snapshot.entries().iter().map(|entry| entry.value).collect();
+    assert_eq!(values, vec!["high", "low 1", "low 2"]);
+
+    let mut restored = DelayQueue::<D, _>::builder()
+        .expiration_order(ExpirationOrder::Fifo)
+        .build();
+    restored.restore(snapshot, RestorePolicy::Shift).unwrap();
+    let items: Vec<_> = restored.map(|entry| entry.into_inner()).collect().await;
+    assert_eq!(items, vec!["high", "low 1", "low 2"]);
+}
@@ -0,0 +1,184 @@
+#![allow(dead_code, unused_imports)]
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use ::tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+use futures::StreamExt;
+use timer_kit::{Delay, DelayQueue, Instant};
+
+use super::*;
+
+/// An event recorded by [`Recorder`], with its fields formatted as strings
+#[derive(Debug, Clone)]
+pub struct Recorded {
+    pub message: String,
+    pub fields: HashMap<&'static str, String>,
+}
+
+/// A subscriber that records the events emitted on the current thread
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    events: Arc<Mutex<Vec<Recorded>>>,
+}
+
+impl Recorder {
+    /// Returns the recorded events whose message is `message`
+    pub fn events(&self, message: &str) -> Vec<Recorded> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.message == message)
+            .cloned()
+            .collect()
+    }
+}
+
+impl Visit for Recorded {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.insert(field.name(), value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.insert(field.name(), format!("{:?}", value));
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut recorded = Recorded {
+            message: String::new(),
+            fields: HashMap::new(),
+        };
+        event.record(&mut recorded);
+        self.events.lock().unwrap().push(recorded);
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+pub async fn sleep_events<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let recorder = Recorder::default();
+    let _guard = ::tracing::subscriber::set_default(recorder.clone());
+
+    let mut sleep = timer_kit::sleep::<D>(Duration::from_millis(10));
+    sleep.reset_after(Duration::from_millis(20));
+    (&mut sleep).await;
+
+    assert_eq!(recorder.events("sleep created").len(), 1);
+    assert_eq!(recorder.events("sleep reset").len(), 1);
+
+    let elapsed = recorder.events("sleep elapsed");
+    assert_eq!(elapsed.len(), 1);
+    assert!(elapsed[0].fields.contains_key("lateness"));
+}
+
+pub async fn timeout_elapsed_with_label<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let recorder = Recorder::default();
+    let _guard = ::tracing::subscriber::set_default(recorder.clone());
+
+    let timeout =
+        timer_kit::timeout::<D, _>(Duration::from_millis(10), never()).with_label("handshake");
+    assert_eq!(timeout.label(), Some("handshake"));
+    assert!(timeout.await.is_err());
+
+    // A timeout whose future completes in time does not emit the event
+    let timeout =
+        timer_kit::timeout::<D, _>(Duration::from_millis(10), async {}).with_label("ready");
+    assert!(timeout.await.is_ok());
+
+    let elapsed = recorder.events("timeout elapsed");
+    assert_eq!(elapsed.len(), 1);
+    assert_eq!(elapsed[0].fields["label"], "handshake");
+}
+
+pub async fn interval_missed_ticks<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let recorder = Recorder::default();
+    let _guard = ::tracing::subscriber::set_default(recorder.clone());
+
+    let mut interval = timer_kit::interval::<D>(Duration::from_millis(10));
+    interval.tick().await;
+    assert!(recorder.events("interval missed ticks").is_empty());
+
+    timer_kit::sleep::<D>(Duration::from_millis(35)).await;
+    interval.tick().await;
+
+    let missed = recorder.events("interval missed ticks");
+    assert_eq!(missed.len(), 1);
+    assert!(missed[0].fields["missed"].parse::<u64>().unwrap() >= 2);
+    assert_eq!(missed[0].fields["behavior"], "Burst");
+}
+
+pub async fn delay_queue_events<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let recorder = Recorder::default();
+    let _guard = ::tracing::subscriber::set_default(recorder.clone());
+
+    let mut queue = DelayQueue::<D, &str>::new();
+    queue.insert("foo", Duration::from_millis(10));
+    let key = queue.insert("bar", Duration::from_millis(10));
+    queue.insert("baz", Duration::from_millis(20));
+    queue.remove(&key);
+
+    while queue.next().await.is_some() {}
+
+    let inserted = recorder.events("delay queue insert");
+    let lens: Vec<_> = inserted.iter().map(|e| e.fields["len"].as_str()).collect();
+    assert_eq!(lens, ["1", "2", "3"]);
+
+    let removed = recorder.events("delay queue remove");
+    assert_eq!(removed.len(), 1);
+    assert_eq!(removed[0].fields["len"], "2");
+
+    let expired: u64 = recorder
+        .events("delay queue expired")
+        .iter()
+        .map(|e| e.fields["count"].parse::<u64>().unwrap())
+        .sum();
+    assert_eq!(expired, 2);
+}
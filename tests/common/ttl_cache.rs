@@ -0,0 +1,154 @@
+#![allow(unused_imports)]
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use timer_kit::{Delay, EvictionCause, Instant, TtlCache};
+
+use super::*;
+
+type Evictions<K, V> = Arc<Mutex<Vec<(K, V, EvictionCause)>>>;
+
+fn cache_with_listener<D, K, V>(capacity: usize, sliding: bool) -> (TtlCache<D, K, V>, Evictions<K, V>)
+where
+    D: Delay,
+    D::Instant: Unpin,
+    K: std::hash::Hash + Eq + Clone + Send + 'static,
+    V: Send + 'static,
+{
+    let evictions = Evictions::default();
+    let listener = evictions.clone();
+    let cache = TtlCache::builder()
+        .capacity(capacity)
+        .sliding_expiration(sliding)
+        .eviction_listener(move |key, value, cause| listener.lock().unwrap().push((key, value, cause)))
+        .build();
+    (cache, evictions)
+}
+
+pub async fn insert_get_and_expire<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let (mut cache, evictions) = cache_with_listener::<D, _, _>(16, false);
+    let start = D::Instant::now();
+    assert_eq!(cache.insert_with_ttl("foo", 1, Duration::from_millis(20)), None);
+    assert_eq!(cache.insert_with_ttl("bar", 2, Duration::from_millis(200)), None);
+    assert_eq!(cache.get("foo"), Some(&1));
+    assert_eq!(cache.insert_with_ttl("foo", 3, Duration::from_millis(20)), Some(1));
+
+    assert_eq!(cache.run_expiry().await, 1);
+    assert!(D::Instant::now() - start >= Duration::from_millis(20));
+    assert_eq!(cache.get("foo"), None);
+    assert_eq!(cache.peek("bar"), Some(&2));
+    assert_eq!(cache.len(), 1);
+    assert_eq!(*evictions.lock().unwrap(), vec![("foo", 3, EvictionCause::Expired)]);
+}
+
+pub async fn sliding_expiration<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let (mut sliding, _) = cache_with_listener::<D, _, _>(16, true);
+    let (mut fixed, _) = cache_with_listener::<D, _, _>(16, false);
+    sliding.insert_with_ttl("foo", 1, Duration::from_millis(50));
+    fixed.insert_with_ttl("foo", 1, Duration::from_millis(50));
+
+    for _ in 0..3 {
+        timer_kit::sleep::<D>(Duration::from_millis(25)).await;
+        assert_eq!(sliding.get("foo"), Some(&1));
+        fixed.get("foo");
+    }
+
+    assert_eq!(sliding.peek("foo"), Some(&1));
+    assert_eq!(fixed.peek("foo"), None);
+}
+
+pub async fn lru_eviction<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let (mut cache, evictions) = cache_with_listener::<D, _, _>(2, false);
+    assert_eq!(cache.capacity(), Some(2));
+    cache.insert_with_ttl("foo", 1, Duration::from_secs(60));
+    cache.insert_with_ttl("bar", 2, Duration::from_secs(60));
+    assert_eq!(cache.get("foo"), Some(&1));
+
+    cache.insert_with_ttl("baz", 3, Duration::from_secs(60));
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains_key("foo"));
+    assert!(!cache.contains_key("bar"));
+    assert!(cache.contains_key("baz"));
+    assert_eq!(*evictions.lock().unwrap(), vec![("bar", 2, EvictionCause::Capacity)]);
+
+    // Peeking does not count as a use
+    assert_eq!(cache.peek("foo"), Some(&1));
+    cache.insert_with_ttl("qux", 4, Duration::from_secs(60));
+    assert!(!cache.contains_key("foo"));
+    assert_eq!(cache.remove("baz"), Some(3));
+    assert_eq!(evictions.lock().unwrap().len(), 2);
+}
+
+pub async fn expired_entries_make_room<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let (mut cache, evictions) = cache_with_listener::<D, _, _>(2, false);
+    cache.insert_with_ttl("foo", 1, Duration::from_secs(60));
+    cache.insert_with_ttl("bar", 2, Duration::from_millis(10));
+    timer_kit::sleep::<D>(Duration::from_millis(20)).await;
+
+    // The expired entry is removed instead of the least recently used live one
+    cache.insert_with_ttl("baz", 3, Duration::from_secs(60));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get("foo"), Some(&1));
+    assert_eq!(cache.get("baz"), Some(&3));
+    assert_eq!(*evictions.lock().unwrap(), vec![("bar", 2, EvictionCause::Expired)]);
+}
+
+pub async fn expired_entries_are_hidden<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let (mut cache, evictions) = cache_with_listener::<D, _, _>(16, true);
+    cache.insert_with_ttl("foo", 1, Duration::from_millis(10));
+    cache.insert_with_ttl("bar", 2, Duration::from_millis(10));
+    timer_kit::sleep::<D>(Duration::from_millis(20)).await;
+
+    // The entries have expired even though `run_expiry` did not run
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get("foo"), None);
+    assert_eq!(cache.peek("foo"), None);
+    assert!(!cache.contains_key("foo"));
+
+    // Replacing or removing an expired entry reports it as expired
+    assert_eq!(cache.insert_with_ttl("foo", 3, Duration::from_secs(60)), None);
+    assert_eq!(cache.remove("bar"), None);
+    assert_eq!(
+        *evictions.lock().unwrap(),
+        vec![("foo", 1, EvictionCause::Expired), ("bar", 2, EvictionCause::Expired)]
+    );
+    assert_eq!(cache.get("foo"), Some(&3));
+    assert_eq!(cache.len(), 1);
+}
+
+pub async fn reused_key_is_not_expired_early<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let (mut cache, evictions) = cache_with_listener::<D, _, _>(16, false);
+    cache.insert_with_ttl("foo", 1, Duration::from_millis(10));
+    assert_eq!(cache.remove("foo"), Some(1));
+    cache.insert_with_ttl("foo", 2, Duration::from_millis(100));
+    cache.insert_with_ttl("bar", 3, Duration::from_millis(30));
+
+    assert_eq!(cache.run_expiry().await, 1);
+    assert_eq!(cache.get("foo"), Some(&2));
+    assert_eq!(*evictions.lock().unwrap(), vec![("bar", 3, EvictionCause::Expired)]);
+}
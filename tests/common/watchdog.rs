@@ -0,0 +1,201 @@
+#![allow(dead_code, unused_imports)]
+
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use timer_kit::{Delay, HeartbeatMonitor, Instant, Watchdog};
+
+use super::*;
+
+pub async fn missed_without_feed<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let start = D::Instant::now();
+    let mut watchdog = Watchdog::<D>::new(Duration::from_millis(100));
+
+    let missed = watchdog.missed().await;
+    assert_eq!(missed.count(), 1);
+    assert!(missed.deadline() >= start + Duration::from_millis(100));
+    assert!(D::Instant::now() - start < Duration::from_millis(110));
+
+    // The watchdog is armed again one timeout later
+    let next = watchdog.missed().await;
+    assert_eq!(next.count(), 2);
+    assert!(next.deadline() == missed.deadline() + Duration::from_millis(100));
+    assert_eq!(watchdog.missed_count(), 2);
+}
+
+pub async fn feed_postpones_the_deadline<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let start = D::Instant::now();
+    let mut watchdog = Watchdog::<D>::new(Duration::from_millis(100));
+
+    timer_kit::sleep::<D>(Duration::from_millis(60)).await;
+    watchdog.feed();
+    timer_kit::sleep::<D>(Duration::from_millis(60)).await;
+    watchdog.feed();
+
+    let missed = watchdog.missed().await;
+    assert_eq!(missed.count(), 1);
+    let elapsed = D::Instant::now() - start;
+    assert!(elapsed >= Duration::from_millis(220));
+    assert!(elapsed < Duration::from_millis(235));
+}
+
+pub async fn feed_resets_the_count<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut watchdog = Watchdog::<D>::new(Duration::from_millis(50));
+    watchdog.missed().await;
+    watchdog.missed().await;
+    assert_eq!(watchdog.missed_count(), 2);
+
+    watchdog.feed();
+    assert_eq!(watchdog.missed_count(), 0);
+    assert_eq!(watchdog.missed().await.count(), 1);
+}
+
+pub async fn feeder<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let start = D::Instant::now();
+    let mut watchdog = Watchdog::<D>::new(Duration::from_millis(100));
+    let feeder = watchdog.feeder();
+
+    let (missed, _) = futures::join!(watchdog.missed(), async move {
+        timer_kit::sleep::<D>(Duration::from_millis(60)).await;
+        feeder.feed();
+    });
+
+    assert_eq!(missed.count(), 1);
+    let elapsed = D::Instant::now() - start;
+    assert!(elapsed >= Duration::from_millis(160));
+    assert!(elapsed < Duration::from_millis(175));
+}
+
+pub async fn feed_from_both_sides<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let start = D::Instant::now();
+    let mut watchdog = Watchdog::<D>::new(Duration::from_millis(100));
+    let feeder = watchdog.feeder();
+
+    // An older feed by the feeder does not move the deadline back
+    timer_kit::sleep::<D>(Duration::from_millis(20)).await;
+    feeder.feed();
+    timer_kit::sleep::<D>(Duration::from_millis(40)).await;
+    watchdog.feed();
+
+    let missed = watchdog.missed().await;
+    assert_eq!(missed.count(), 1);
+    let elapsed = D::Instant::now() - start;
+    assert!(elapsed >= Duration::from_millis(160));
+    assert!(elapsed < Duration::from_millis(175));
+
+    // A later feed by the feeder does move the deadline forward
+    watchdog.feed();
+    timer_kit::sleep::<D>(Duration::from_millis(20)).await;
+    let fed = D::Instant::now();
+    feeder.feed();
+
+    let missed = watchdog.missed().await;
+    assert_eq!(missed.count(), 1);
+    assert!(missed.deadline() >= fed + Duration::from_millis(100));
+}
+
+pub async fn run<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut watchdog = Watchdog::<D>::new(Duration::from_millis(100));
+    let mut counts = Vec::new();
+
+    let result = timer_kit::timeout::<D, _>(Duration::from_millis(250), watchdog.run(|missed| {
+        counts.push(missed.count());
+    }))
+    .await;
+    assert!(result.is_err());
+    assert_eq!(counts, [1, 2]);
+}
+
+pub async fn stream<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let watchdog = Watchdog::<D>::new(Duration::from_millis(50));
+    let counts: Vec<_> = watchdog.take(3).map(|missed| missed.count()).collect().await;
+    assert_eq!(counts, [1, 2, 3]);
+}
+
+pub async fn silent_peers<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let start = D::Instant::now();
+    let mut monitor = HeartbeatMonitor::<D, &str>::new(Duration::from_millis(100));
+    assert!(monitor.heartbeat("foo").is_none());
+    assert!(monitor.heartbeat("bar").is_none());
+
+    timer_kit::sleep::<D>(Duration::from_millis(60)).await;
+    let seen = D::Instant::now();
+    assert!(monitor.heartbeat("foo").is_some());
+    assert!(monitor.last_seen("foo").unwrap() >= seen);
+
+    let silent = monitor.next().await.unwrap();
+    assert_eq!(*silent.key(), "bar");
+    assert!(silent.last_seen() < seen);
+    let elapsed = D::Instant::now() - start;
+    assert!(elapsed >= Duration::from_millis(100));
+    assert!(elapsed < Duration::from_millis(110));
+
+    let silent = monitor.next().await.unwrap();
+    assert_eq!(silent.into_key(), "foo");
+    let elapsed = D::Instant::now() - start;
+    assert!(elapsed >= Duration::from_millis(160));
+    assert!(elapsed < Duration::from_millis(175));
+
+    assert!(monitor.is_empty());
+    assert_ready_none!(monitor.next());
+}
+
+pub async fn heartbeat_tracks_peers<D>()
+where
+    D: Delay,
+    D::Instant: Unpin,
+{
+    let mut monitor = HeartbeatMonitor::<D, &str>::new(Duration::from_millis(100));
+    let seen = D::Instant::now();
+    monitor.heartbeat_at("foo", seen);
+    monitor.heartbeat_at("bar", seen);
+
+    assert_eq!(monitor.len(), 2);
+    assert!(monitor.contains("foo"));
+    assert!(monitor.last_seen("foo") == Some(seen));
+    assert!(monitor.deadline("foo").unwrap() >= seen + Duration::from_millis(100));
+
+    let later = seen + Duration::from_millis(50);
+    assert!(monitor.heartbeat_at("foo", later) == Some(seen));
+    assert!(monitor.deadline("foo").unwrap() >= later + Duration::from_millis(100));
+
+    assert!(monitor.remove("bar") == Some(seen));
+    assert!(!monitor.contains("bar"));
+    let peers: Vec<_> = monitor.peers().map(|(key, _)| *key).collect();
+    assert_eq!(peers, ["foo"]);
+
+    monitor.clear();
+    assert!(monitor.is_empty());
+}
@@ -0,0 +1,407 @@
+//! The timers driven by a manually advanced clock, as a `no_std` user would
+//! provide for a hardware timer.
+
+use std::cell::Cell;
+use std::future::Future;
+use std::ops::{Add, Sub};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::task::noop_waker_ref;
+use timer_kit::{
+    error::DelayQueueError, Delay, DelayQueue, ElapsedHistogram, FarDeadlineBehavior, Instant,
+    MissedTickBehavior, Stopwatch,
+};
+
+thread_local! {
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+fn advance(duration: Duration) {
+    NOW.with(|now| now.set(now.get() + duration));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct MockInstant(Duration);
+
+impl Add<Duration> for MockInstant {
+    type Output = Self;
+
+    fn add(self, rhs: Duration) -> Self {
+        Self(self.0 + rhs)
+    }
+}
+
+impl Sub<Duration> for MockInstant {
+    type Output = Self;
+
+    fn sub(self, rhs: Duration) -> Self {
+        Self(self.0.saturating_sub(rhs))
+    }
+}
+
+impl Sub<MockInstant> for MockInstant {
+    type Output = Duration;
+
+    fn sub(self, rhs: Self) -> Duration {
+        self.0.saturating_sub(rhs.0)
+    }
+}
+
+impl Instant for MockInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+}
+
+#[derive(Debug)]
+struct MockDelay {
+    deadline: MockInstant,
+}
+
+impl Delay for MockDelay {
+    type Value = ();
+    type Instant = MockInstant;
+
+    fn delay(duration: Duration) -> Self {
+        Self::delay_until(MockInstant::now() + duration)
+    }
+
+    fn delay_until(deadline: MockInstant) -> Self {
+        Self { deadline }
+    }
+
+    fn deadline(&self) -> Option<MockInstant> {
+        Some(self.deadline)
+    }
+
+    fn poll_elapsed(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+        if MockInstant::now() >= self.deadline {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn reset(mut self: Pin<&mut Self>, deadline: MockInstant) {
+        self.deadline = deadline;
+    }
+}
+
+fn poll<F: Future + Unpin>(fut: &mut F) -> Poll<F::Output> {
+    Pin::new(fut).poll(&mut Context::from_waker(noop_waker_ref()))
+}
+
+#[test]
+fn sleep() {
+    let mut sleep = timer_kit::sleep::<MockDelay>(Duration::from_millis(10));
+    assert!(poll(&mut sleep).is_pending());
+
+    advance(Duration::from_millis(9));
+    assert!(poll(&mut sleep).is_pending());
+    advance(Duration::from_millis(1));
+    assert!(poll(&mut sleep).is_ready());
+}
+
+#[test]
+fn timeout() {
+    let mut timeout = timer_kit::timeout::<MockDelay, _>(Duration::from_millis(10), std::future::pending::<()>());
+    assert!(poll(&mut timeout).is_pending());
+
+    advance(Duration::from_millis(10));
+    assert!(matches!(poll(&mut timeout), Poll::Ready(Err(_))));
+}
+
+#[test]
+fn interval() {
+    let mut interval = timer_kit::interval::<MockDelay>(Duration::from_millis(10));
+    let mut cx = Context::from_waker(noop_waker_ref());
+    assert!(interval.poll_tick(&mut cx).is_pending());
+
+    for _ in 0..3 {
+        advance(Duration::from_millis(10));
+        assert!(interval.poll_tick(&mut cx).is_ready());
+        assert!(interval.poll_tick(&mut cx).is_pending());
+    }
+}
+
+#[test]
+fn delay_queue() {
+    let mut queue = DelayQueue::<MockDelay, _>::new();
+    let mut cx = Context::from_waker(noop_waker_ref());
+    queue.insert("bar", Duration::from_millis(20));
+    queue.insert("foo", Duration::from_millis(10));
+    queue.insert("baz", Duration::from_secs(60 * 60));
+    assert!(queue.poll_expired(&mut cx).is_pending());
+
+    advance(Duration::from_millis(25));
+    let mut expired = vec![];
+    while let Poll::Ready(Some(entry)) = queue.poll_expired(&mut cx) {
+        expired.push(entry.into_inner());
+    }
+    assert_eq!(expired, vec!["foo", "bar"]);
+
+    advance(Duration::from_secs(60 * 60));
+    assert_eq!(queue.poll_expired(&mut cx).map(|entry| entry.map(|e| e.into_inner())), Poll::Ready(Some("baz")));
+    assert!(queue.poll_expired(&mut cx).is_ready());
+}
+
+#[test]
+fn delay_queue_jump_past_overflow() {
+    // At a resolution of 1µs, the range of the timer wheel is about 19 hours
+    let hour = Duration::from_secs(60 * 60);
+    let mut queue = DelayQueue::<MockDelay, _>::builder()
+        .resolution(Duration::from_micros(1))
+        .build();
+    queue.insert("far", hour * 20);
+    queue.insert("farther", hour * 20 + Duration::from_secs(1));
+
+    // Jump past the overflowing deadlines at once
+    advance(hour * 21);
+    let expired: Vec<_> = queue
+        .drain_expired()
+        .into_iter()
+        .map(|entry| entry.into_inner())
+        .collect();
+    assert_eq!(expired, ["far", "farther"]);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn drain_expired_with_overflow() {
+    let hour = Duration::from_secs(60 * 60);
+    let mut queue = DelayQueue::<MockDelay, _>::builder()
+        .resolution(Duration::from_micros(1))
+        .build();
+    queue.insert("overflow", hour * 20);
+    queue.insert("first", hour);
+    queue.insert("last", hour * 20 + Duration::from_secs(1));
+    queue.insert("in range", hour * 19);
+    queue.insert("pending", hour * 22);
+
+    advance(hour * 21);
+    let expired: Vec<_> = queue
+        .drain_expired()
+        .into_iter()
+        .map(|entry| entry.into_inner())
+        .collect();
+    assert_eq!(expired, ["first", "in range", "overflow", "last"]);
+    assert_eq!(queue.len(), 1);
+
+    advance(hour);
+    let expired: Vec<_> = queue
+        .drain_expired()
+        .into_iter()
+        .map(|entry| entry.into_inner())
+        .collect();
+    assert_eq!(expired, ["pending"]);
+}
+
+#[test]
+fn far_deadline_behavior_on_idle_queue() {
+    let hour = Duration::from_secs(60 * 60);
+    let mut reject = DelayQueue::<MockDelay, _>::builder()
+        .resolution(Duration::from_micros(1))
+        .far_deadline_behavior(FarDeadlineBehavior::Reject)
+        .build();
+    let mut clamp = DelayQueue::<MockDelay, _>::builder()
+        .resolution(Duration::from_micros(1))
+        .far_deadline_behavior(FarDeadlineBehavior::Clamp)
+        .build();
+    let mut reject_ms = DelayQueue::<MockDelay, _>::builder()
+        .far_deadline_behavior(FarDeadlineBehavior::Reject)
+        .build();
+    let mut cx = Context::from_waker(noop_waker_ref());
+
+    // The queues are idle for longer than the range of their wheel
+    advance(hour * 20);
+
+    assert!(reject.try_insert("soon", Duration::from_secs(1)).is_ok());
+    assert_eq!(
+        reject.try_insert("far", hour * 20),
+        Err(DelayQueueError::DeadlineOutOfRange)
+    );
+
+    // The range scales with the resolution
+    assert!(reject_ms.try_insert("far", hour * 20).is_ok());
+
+    let key = clamp.insert("soon", Duration::from_secs(60));
+    assert_eq!(
+        clamp.deadline(&key),
+        Some(MockInstant::now() + Duration::from_secs(60))
+    );
+    assert!(clamp.poll_expired(&mut cx).is_pending());
+    let key = clamp.insert("far", hour * 20);
+    assert!(clamp.deadline(&key).unwrap() < MockInstant::now() + hour * 20);
+
+    advance(Duration::from_secs(60));
+    assert_eq!(
+        clamp
+            .poll_expired(&mut cx)
+            .map(|entry| entry.map(|e| e.into_inner())),
+        Poll::Ready(Some("soon"))
+    );
+}
+
+#[test]
+fn instant_default_methods() {
+    let now = MockInstant::now();
+    let far_future = MockInstant::far_future();
+    assert_eq!(far_future - now, Duration::from_secs(86400 * 365 * 30));
+
+    assert_eq!(now.checked_add(Duration::from_secs(1)), Some(now + Duration::from_secs(1)));
+    assert_eq!(now.checked_add(Duration::MAX), None);
+    assert_eq!(
+        now.checked_add(Duration::from_secs(86400 * 365 * 30) + Duration::from_nanos(1)),
+        None
+    );
+    assert_eq!(now.checked_sub(Duration::MAX), None);
+    assert_eq!(
+        far_future.checked_sub(Duration::from_secs(1)),
+        Some(far_future - Duration::from_secs(1))
+    );
+
+    assert_eq!(far_future.duration_since(now), Duration::from_secs(86400 * 365 * 30));
+    assert_eq!(now.saturating_duration_since(far_future), Duration::ZERO);
+    assert_eq!(now.duration_since(far_future), Duration::ZERO);
+}
+
+#[test]
+fn instant_default_methods_near_the_end_of_the_clock() {
+    // The ticks of a `MockInstant` are the seconds of a `Duration`, which are a `u64`
+    let edge = MockInstant(Duration::from_secs(u64::MAX - 60));
+    assert_eq!(
+        edge.checked_add(Duration::from_secs(60)),
+        Some(MockInstant(Duration::from_secs(u64::MAX)))
+    );
+    assert_eq!(
+        edge.checked_sub(Duration::from_secs(60)),
+        Some(MockInstant(Duration::from_secs(u64::MAX - 120)))
+    );
+
+    // Durations longer than about 30 years are out of range, even when the clock can represent
+    // the result
+    assert_eq!(edge.checked_sub(Duration::from_secs(86400 * 365 * 31)), None);
+    let start = MockInstant(Duration::ZERO);
+    assert_eq!(start.checked_add(Duration::from_secs(86400 * 365 * 31)), None);
+}
+
+#[test]
+#[should_panic(expected = "overflow")]
+fn instant_default_checked_add_panics_past_the_end_of_the_clock() {
+    // The default implementation relies on `Add`, which `MockInstant` does not check
+    let edge = MockInstant(Duration::from_secs(u64::MAX - 60));
+    let _ = edge.checked_add(Duration::from_secs(61));
+}
+
+#[test]
+fn extreme_deadlines() {
+    let mut queue = DelayQueue::<MockDelay, _>::new();
+    let mut cx = Context::from_waker(noop_waker_ref());
+    let key = queue.insert("far", Duration::MAX);
+    assert!(queue.deadline(&key).unwrap() >= MockInstant::far_future());
+    queue.reset(&key, Duration::MAX);
+    assert!(queue.poll_expired(&mut cx).is_pending());
+
+    // The next tick of a late interval saturates instead of overflowing
+    for behavior in [MissedTickBehavior::Burst, MissedTickBehavior::Delay, MissedTickBehavior::Skip] {
+        let mut interval = timer_kit::interval_at::<MockDelay>(MockInstant::now(), Duration::MAX);
+        interval.set_missed_tick_behavior(behavior);
+        advance(Duration::from_secs(1));
+        assert!(interval.poll_tick(&mut cx).is_ready());
+        assert!(interval.poll_tick(&mut cx).is_pending());
+    }
+
+    // The lateness threshold saturates with a maximum slack
+    let mut interval = timer_kit::interval::<MockDelay>(Duration::from_millis(10));
+    interval.set_slack(Duration::MAX);
+    advance(Duration::from_millis(10));
+    assert!(interval.poll_tick(&mut cx).is_ready());
+
+    let mut sleep = timer_kit::sleep_until::<MockDelay>(MockInstant::far_future());
+    sleep.reset_after(Duration::MAX);
+    assert!(sleep.deadline() >= MockInstant::far_future());
+    assert!(poll(&mut sleep).is_pending());
+}
+
+#[test]
+fn stopwatch() {
+    let mut stopwatch = Stopwatch::<MockInstant>::start_new();
+    advance(Duration::from_millis(10));
+    assert_eq!(stopwatch.lap(), Duration::from_millis(10));
+
+    advance(Duration::from_millis(5));
+    assert_eq!(stopwatch.split(), Duration::from_millis(5));
+    advance(Duration::from_millis(5));
+    assert_eq!(stopwatch.lap(), Duration::from_millis(10));
+
+    // Time does not count while the stopwatch is stopped
+    assert_eq!(stopwatch.stop(), Duration::from_millis(20));
+    advance(Duration::from_millis(100));
+    assert!(!stopwatch.is_running());
+    assert_eq!(stopwatch.elapsed(), Duration::from_millis(20));
+
+    stopwatch.start();
+    advance(Duration::from_millis(10));
+    assert_eq!(stopwatch.elapsed(), Duration::from_millis(30));
+    assert_eq!(stopwatch.lap(), Duration::from_millis(10));
+
+    stopwatch.restart();
+    assert_eq!(stopwatch.elapsed(), Duration::ZERO);
+    assert!(stopwatch.is_running());
+    stopwatch.reset();
+    assert!(!stopwatch.is_running());
+}
+
+#[test]
+fn measure() {
+    let mut measure = timer_kit::measure::<MockInstant, _>(timer_kit::sleep::<MockDelay>(Duration::from_millis(10)));
+
+    // The measurement starts at the first poll
+    advance(Duration::from_millis(5));
+    assert!(poll(&mut measure).is_pending());
+    advance(Duration::from_millis(20));
+    assert_eq!(poll(&mut measure).map(|(_, elapsed)| elapsed), Poll::Ready(Duration::from_millis(20)));
+}
+
+#[test]
+fn elapsed_histogram() {
+    let bounds = [Duration::from_millis(10), Duration::from_millis(1), Duration::from_millis(100)];
+    let mut histogram = ElapsedHistogram::with_bounds(bounds);
+    assert_eq!(histogram.quantile(0.5), None);
+    assert_eq!(histogram.mean(), None);
+
+    for millis in [1, 2, 5, 20, 500] {
+        histogram.record(Duration::from_millis(millis));
+    }
+    assert_eq!(histogram.count(), 5);
+    assert_eq!(histogram.sum(), Duration::from_millis(528));
+    assert_eq!(histogram.mean(), Some(Duration::from_micros(105_600)));
+    assert_eq!(histogram.min(), Some(Duration::from_millis(1)));
+    assert_eq!(histogram.max(), Some(Duration::from_millis(500)));
+
+    let buckets: Vec<_> = histogram.buckets().collect();
+    assert_eq!(buckets, [
+        (Some(Duration::from_millis(1)), 1),
+        (Some(Duration::from_millis(10)), 2),
+        (Some(Duration::from_millis(100)), 1),
+        (None, 1),
+    ]);
+    assert_eq!(histogram.quantile(0.0), Some(Duration::from_millis(1)));
+    assert_eq!(histogram.quantile(0.5), Some(Duration::from_millis(10)));
+    assert_eq!(histogram.quantile(0.6), Some(Duration::from_millis(10)));
+    assert_eq!(histogram.quantile(0.61), Some(Duration::from_millis(100)));
+    assert_eq!(histogram.quantile(1.0), Some(Duration::from_millis(500)));
+
+    let mut sleep = Box::pin(histogram.measure::<MockInstant, _>(timer_kit::sleep::<MockDelay>(Duration::from_millis(50))));
+    assert!(poll(&mut sleep).is_pending());
+    advance(Duration::from_millis(50));
+    assert!(poll(&mut sleep).is_ready());
+    drop(sleep);
+    assert_eq!(histogram.count(), 6);
+
+    histogram.clear();
+    assert_eq!(histogram.count(), 0);
+    assert_eq!(histogram.buckets().map(|(_, count)| count).sum::<u64>(), 0);
+}
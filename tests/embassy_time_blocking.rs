@@ -0,0 +1,56 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_embassy_time! {
+        cfg_std! {
+            #[test]
+            fn sleep_until() {
+                common::blocking::sleep_until::<timer_kit::embassy::Timer>();
+            }
+
+            #[test]
+            fn sleep() {
+                common::blocking::sleep::<timer_kit::embassy::Timer>();
+            }
+
+            #[test]
+            fn tick_blocking() {
+                common::blocking::tick_blocking::<timer_kit::embassy::Timer>();
+            }
+
+            #[test]
+            fn tick_blocking_skips_missed_ticks() {
+                common::blocking::tick_blocking_skips_missed_ticks::<timer_kit::embassy::Timer>();
+            }
+
+            #[test]
+            fn next_expired_blocking() {
+                common::blocking::next_expired_blocking::<timer_kit::embassy::Timer>();
+            }
+
+            #[test]
+            fn next_expired_blocking_times_out() {
+                common::blocking::next_expired_blocking_times_out::<timer_kit::embassy::Timer>();
+            }
+
+            #[test]
+            fn timeout() {
+                common::blocking::timeout::<timer_kit::embassy::Timer>();
+            }
+
+            #[test]
+            fn timeout_elapsed() {
+                common::blocking::timeout_elapsed::<timer_kit::embassy::Timer>();
+            }
+
+            #[test]
+            #[should_panic(expected = "the closure passed to `blocking::timeout` panicked")]
+            fn timeout_panic() {
+                common::blocking::timeout_panic::<timer_kit::embassy::Timer>();
+            }
+        }
+    }
+}
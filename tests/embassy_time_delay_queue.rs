@@ -0,0 +1,304 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_embassy_time! {
+        #[futures_test::test]
+        async fn single_immediate_delay() {
+            common::delay_queue::single_immediate_delay::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn multiple_immediate_delay() {
+            common::delay_queue::multiple_immediate_delay::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn single_short_delay() {
+            common::delay_queue::single_short_delay::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn multi_delay_at_start() {
+            common::delay_queue::multi_delay_at_start::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn insert_in_past_fires_immediately() {
+            common::delay_queue::insert_in_past_fires_immediately::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn remove_entry() {
+            common::delay_queue::remove_entry::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn reset_entry() {
+            common::delay_queue::reset_entry::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn reset_much_later() {
+            common::delay_queue::reset_much_later::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn reset_twice() {
+            common::delay_queue::reset_twice::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn repeatedly_reset_entry_inserted_as_expired() {
+            common::delay_queue::repeatedly_reset_entry_inserted_as_expired::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn remove_expired_item() {
+            common::delay_queue::remove_expired_item::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn remove_at_timer_wheel_threshold() {
+            common::delay_queue::remove_at_timer_wheel_threshold::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn expires_before_last_insert() {
+            common::delay_queue::expires_before_last_insert::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn multi_reset() {
+            common::delay_queue::multi_reset::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn expire_first_key_when_reset_to_expire_earlier() {
+            common::delay_queue::expire_first_key_when_reset_to_expire_earlier::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn expire_second_key_when_reset_to_expire_earlier() {
+            common::delay_queue::expire_second_key_when_reset_to_expire_earlier::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn reset_first_expiring_item_to_expire_later() {
+            common::delay_queue::reset_first_expiring_item_to_expire_later::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn insert_before_first_after_poll() {
+            common::delay_queue::insert_before_first_after_poll::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn insert_after_ready_poll() {
+            common::delay_queue::insert_after_ready_poll::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn reset_later_after_slot_starts() {
+            common::delay_queue::reset_later_after_slot_starts::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn reset_insert_expired() {
+            common::delay_queue::reset_insert_expired::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn reset_earlier_after_slot_starts() {
+            common::delay_queue::reset_earlier_after_slot_starts::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn insert_in_past_after_poll_fires_immediately() {
+            common::delay_queue::insert_in_past_after_poll_fires_immediately::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn delay_queue_poll_expired_when_empty() {
+            common::delay_queue::delay_queue_poll_expired_when_empty::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn compact_expire_empty() {
+            common::delay_queue::compact_expire_empty::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn compact_remove_empty() {
+            common::delay_queue::compact_remove_empty::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn compact_remove_remapped_keys() {
+            common::delay_queue::compact_remove_remapped_keys::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn compact_change_deadline() {
+            common::delay_queue::compact_change_deadline::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn remove_after_compact() {
+            common::delay_queue::remove_after_compact::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        #[should_panic]
+        async fn panic_on_remove_of_nonexistent_key() {
+            common::delay_queue::panic_on_remove_of_nonexistent_key::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        #[should_panic]
+        async fn panic_on_remove_after_compact_poll() {
+            common::delay_queue::panic_on_remove_after_compact_poll::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn try_insert_far_deadline_rejected() {
+            common::delay_queue::try_insert_far_deadline_rejected::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn try_insert_far_deadline_clamped() {
+            common::delay_queue::try_insert_far_deadline_clamped::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn try_reset_invalid_key() {
+            common::delay_queue::try_reset_invalid_key::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        #[should_panic]
+        async fn panic_on_insert_far_deadline() {
+            common::delay_queue::panic_on_insert_far_deadline::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn far_deadline_overflow() {
+            common::delay_queue::far_deadline_overflow::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn coarse_resolution() {
+            common::delay_queue::coarse_resolution::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn fine_resolution() {
+            common::delay_queue::fine_resolution::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn get_and_get_mut() {
+            common::delay_queue::get_and_get_mut::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn deadline_of_key() {
+            common::delay_queue::deadline_of_key::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn peek_next_deadline() {
+            common::delay_queue::peek_next_deadline::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn peek_next_deadline_expired() {
+            common::delay_queue::peek_next_deadline_expired::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn iter_and_iter_mut() {
+            common::delay_queue::iter_and_iter_mut::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn stale_key_is_invalid() {
+            common::delay_queue::stale_key_is_invalid::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn stale_key_after_compact() {
+            common::delay_queue::stale_key_after_compact::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        #[should_panic]
+        async fn panic_on_remove_of_stale_key() {
+            common::delay_queue::panic_on_remove_of_stale_key::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        #[should_panic]
+        async fn panic_on_reset_of_stale_key() {
+            common::delay_queue::panic_on_reset_of_stale_key::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn poll_expired_batch() {
+            common::delay_queue::poll_expired_batch::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn drain_expired() {
+            common::delay_queue::drain_expired::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn retain() {
+            common::delay_queue::retain::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn retain_updates_delay() {
+            common::delay_queue::retain_updates_delay::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn extend_and_from_iter() {
+            common::delay_queue::extend_and_from_iter::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn extend_after_poll() {
+            common::delay_queue::extend_after_poll::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        #[should_panic]
+        async fn panic_on_extend_far_deadline() {
+            common::delay_queue::panic_on_extend_far_deadline::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn fifo_equal_deadlines() {
+            common::delay_queue::fifo_equal_deadlines::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn fifo_expired_entries() {
+            common::delay_queue::fifo_expired_entries::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn fifo_across_levels() {
+            common::delay_queue::fifo_across_levels::<timer_kit::embassy::Timer>().await;
+        }
+    
+        #[futures_test::test]
+        async fn fifo_with_priority() {
+            common::delay_queue::fifo_with_priority::<timer_kit::embassy::Timer>().await;
+        }
+    }
+}
\ No newline at end of file
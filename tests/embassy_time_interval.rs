@@ -0,0 +1,34 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_embassy_time! {
+        #[futures_test::test]
+        #[should_panic]
+        async fn interval_zero_duration() {
+            common::interval::interval_zero_duration::<timer_kit::embassy::Timer>().await;
+        }
+
+        #[futures_test::test]
+        async fn burst() {
+            common::interval::burst::<timer_kit::embassy::Timer>().await;
+        }
+
+        #[futures_test::test]
+        async fn delay() {
+            common::interval::delay::<timer_kit::embassy::Timer>().await;
+        }
+
+        #[futures_test::test]
+        async fn skip() {
+            common::interval::skip::<timer_kit::embassy::Timer>().await;
+        }
+
+        #[futures_test::test]
+        async fn reset() {
+            common::interval::reset::<timer_kit::embassy::Timer>().await;
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,43 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_embassy_time! {
+        #[futures_test::test]
+        async fn immediate_sleep() {
+            common::sleep::immediate_sleep::<timer_kit::embassy::Timer>().await;
+        }
+
+        #[futures_test::test]
+        async fn short_sleep() {
+            common::sleep::short_sleep::<timer_kit::embassy::Timer>().await;
+        }
+
+        #[futures_test::test]
+        async fn reset() {
+            common::sleep::reset::<timer_kit::embassy::Timer>().await;
+        }
+
+        #[futures_test::test]
+        async fn is_elapsed_and_remaining() {
+            common::sleep::is_elapsed_and_remaining::<timer_kit::embassy::Timer>().await;
+        }
+
+        #[futures_test::test]
+        async fn reset_after() {
+            common::sleep::reset_after::<timer_kit::embassy::Timer>().await;
+        }
+
+        #[futures_test::test]
+        async fn never() {
+            common::sleep::never::<timer_kit::embassy::Timer>().await;
+        }
+
+        #[futures_test::test]
+        async fn max_duration() {
+            common::sleep::max_duration::<timer_kit::embassy::Timer>().await;
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,28 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_embassy_time! {
+        #[futures_test::test]
+        async fn simultaneous_deadline_future_completion() {
+            common::timeout::simultaneous_deadline_future_completion::<timer_kit::embassy::Timer>().await;
+        }
+
+        #[futures_test::test]
+        async fn completed_future_past_deadline() {
+            common::timeout::completed_future_past_deadline::<timer_kit::embassy::Timer>().await;
+        }
+
+        #[futures_test::test]
+        async fn future_and_deadline_in_future() {
+            common::timeout::future_and_deadline_in_future::<timer_kit::embassy::Timer>().await;
+        }
+
+        #[futures_test::test]
+        async fn deadline_future_elapses() {
+            common::timeout::deadline_future_elapses::<timer_kit::embassy::Timer>().await;
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,42 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_wasm32! {
+    cfg_fluvio_wasm_timer! {
+        use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        #[wasm_bindgen_test]
+        async fn overlapping_sleeps_share_a_delay() {
+            common::coalesce::overlapping_sleeps_share_a_delay::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn disjoint_sleeps_use_separate_delays() {
+            common::coalesce::disjoint_sleeps_use_separate_delays::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn dropped_sleep_leaves_its_group() {
+            common::coalesce::dropped_sleep_leaves_its_group::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn interval_joins_other_timers() {
+            common::coalesce::interval_joins_other_timers::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn delay_queue_joins_other_timers() {
+            common::coalesce::delay_queue_joins_other_timers::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn reset_within_slack_keeps_the_delay() {
+            common::coalesce::reset_within_slack_keeps_the_delay::<fluvio_wasm_timer::Delay>().await;
+        }
+    }
+}
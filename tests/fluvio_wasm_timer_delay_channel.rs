@@ -0,0 +1,37 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_wasm32! {
+    cfg_fluvio_wasm_timer! {
+        use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        #[wasm_bindgen_test]
+        async fn send_and_receive() {
+            common::delay_channel::send_and_receive::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn cancel_message() {
+            common::delay_channel::cancel_message::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn cancel_after_receive() {
+            common::delay_channel::cancel_after_receive::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn send_from_clones() {
+            common::delay_channel::send_from_clones::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn send_after_receiver_dropped() {
+            common::delay_channel::send_after_receiver_dropped::<fluvio_wasm_timer::Delay>().await;
+        }
+    }
+}
@@ -0,0 +1,32 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_wasm32! {
+    cfg_fluvio_wasm_timer! {
+        use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        #[wasm_bindgen_test]
+        async fn insert_and_expire() {
+            common::delay_map::insert_and_expire::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn insert_replaces_existing() {
+            common::delay_map::insert_replaces_existing::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn remove_and_reset() {
+            common::delay_map::remove_and_reset::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn reinsert_after_expiry() {
+            common::delay_map::reinsert_after_expiry::<fluvio_wasm_timer::Delay>().await;
+        }
+    }
+}
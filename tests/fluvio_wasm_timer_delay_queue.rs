@@ -165,5 +165,144 @@ cfg_wasm32! {
         // async fn panic_on_remove_after_compact_poll() {
         //     common::delay_queue::panic_on_remove_after_compact_poll::<fluvio_wasm_timer::Delay>().await;
         // }
+
+        #[wasm_bindgen_test]
+        async fn try_insert_far_deadline_rejected() {
+            common::delay_queue::try_insert_far_deadline_rejected::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn try_insert_far_deadline_clamped() {
+            common::delay_queue::try_insert_far_deadline_clamped::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn try_reset_invalid_key() {
+            common::delay_queue::try_reset_invalid_key::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        // #[wasm_bindgen_test]
+        // #[should_panic]
+        // async fn panic_on_insert_far_deadline() {
+        //     common::delay_queue::panic_on_insert_far_deadline::<fluvio_wasm_timer::Delay>().await;
+        // }
+
+        #[wasm_bindgen_test]
+        async fn far_deadline_overflow() {
+            common::delay_queue::far_deadline_overflow::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn coarse_resolution() {
+            common::delay_queue::coarse_resolution::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn fine_resolution() {
+            common::delay_queue::fine_resolution::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn get_and_get_mut() {
+            common::delay_queue::get_and_get_mut::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn deadline_of_key() {
+            common::delay_queue::deadline_of_key::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn peek_next_deadline() {
+            common::delay_queue::peek_next_deadline::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn peek_next_deadline_expired() {
+            common::delay_queue::peek_next_deadline_expired::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn iter_and_iter_mut() {
+            common::delay_queue::iter_and_iter_mut::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn stale_key_is_invalid() {
+            common::delay_queue::stale_key_is_invalid::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn stale_key_after_compact() {
+            common::delay_queue::stale_key_after_compact::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        // #[wasm_bindgen_test]
+        // #[should_panic]
+        // async fn panic_on_remove_of_stale_key() {
+        //     common::delay_queue::panic_on_remove_of_stale_key::<fluvio_wasm_timer::Delay>().await;
+        // }
+
+        // #[wasm_bindgen_test]
+        // #[should_panic]
+        // async fn panic_on_reset_of_stale_key() {
+        //     common::delay_queue::panic_on_reset_of_stale_key::<fluvio_wasm_timer::Delay>().await;
+        // }
+
+        #[wasm_bindgen_test]
+        async fn poll_expired_batch() {
+            common::delay_queue::poll_expired_batch::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn drain_expired() {
+            common::delay_queue::drain_expired::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn retain() {
+            common::delay_queue::retain::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn retain_updates_delay() {
+            common::delay_queue::retain_updates_delay::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn extend_and_from_iter() {
+            common::delay_queue::extend_and_from_iter::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn extend_after_poll() {
+            common::delay_queue::extend_after_poll::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        // #[wasm_bindgen_test]
+        // #[should_panic]
+        // async fn panic_on_extend_far_deadline() {
+        //     common::delay_queue::panic_on_extend_far_deadline::<fluvio_wasm_timer::Delay>().await;
+        // }
+
+        #[wasm_bindgen_test]
+        async fn fifo_equal_deadlines() {
+            common::delay_queue::fifo_equal_deadlines::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn fifo_expired_entries() {
+            common::delay_queue::fifo_expired_entries::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn fifo_across_levels() {
+            common::delay_queue::fifo_across_levels::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn fifo_with_priority() {
+            common::delay_queue::fifo_with_priority::<fluvio_wasm_timer::Delay>().await;
+        }
     }
 }
\ No newline at end of file
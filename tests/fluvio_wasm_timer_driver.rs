@@ -0,0 +1,47 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_wasm32! {
+    cfg_fluvio_wasm_timer! {
+        use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        #[wasm_bindgen_test]
+        async fn many_sleeps_share_the_driver() {
+            common::driver::many_sleeps_share_the_driver::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn sleep_completes_at_its_deadline() {
+            common::driver::sleep_completes_at_its_deadline::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn earlier_sleep_rearms_the_delay() {
+            common::driver::earlier_sleep_rearms_the_delay::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn dropped_sleep_is_cancelled() {
+            common::driver::dropped_sleep_is_cancelled::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn reset() {
+            common::driver::reset::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn timeout() {
+            common::driver::timeout::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn driver_completes_once_dropped() {
+            common::driver::driver_completes_once_dropped::<fluvio_wasm_timer::Delay>().await;
+        }
+    }
+}
@@ -0,0 +1,36 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_wasm32! {
+    cfg_fluvio_wasm_timer! {
+        use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+        wasm_bindgen_test_configure!(run_in_browser);
+        #[wasm_bindgen_test]
+        async fn insert_from_task() {
+            common::shared_delay_queue::insert_from_task::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn earlier_insert_wakes_consumer() {
+            common::shared_delay_queue::earlier_insert_wakes_consumer::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn remove_and_reset() {
+            common::shared_delay_queue::remove_and_reset::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn stream_ends_when_inserters_dropped() {
+            common::shared_delay_queue::stream_ends_when_inserters_dropped::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn insert_after_consumer_dropped() {
+            common::shared_delay_queue::insert_after_consumer_dropped::<fluvio_wasm_timer::Delay>().await;
+        }
+    }
+}
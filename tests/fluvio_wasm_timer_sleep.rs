@@ -23,5 +23,25 @@ cfg_wasm32! {
         async fn reset() {
             common::sleep::reset::<fluvio_wasm_timer::Delay>().await;
         }
+
+        #[wasm_bindgen_test]
+        async fn is_elapsed_and_remaining() {
+            common::sleep::is_elapsed_and_remaining::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn reset_after() {
+            common::sleep::reset_after::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn never() {
+            common::sleep::never::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn max_duration() {
+            common::sleep::max_duration::<fluvio_wasm_timer::Delay>().await;
+        }
     }
 }
\ No newline at end of file
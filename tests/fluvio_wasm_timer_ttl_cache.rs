@@ -0,0 +1,42 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_wasm32! {
+    cfg_fluvio_wasm_timer! {
+        use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        #[wasm_bindgen_test]
+        async fn insert_get_and_expire() {
+            common::ttl_cache::insert_get_and_expire::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn sliding_expiration() {
+            common::ttl_cache::sliding_expiration::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn lru_eviction() {
+            common::ttl_cache::lru_eviction::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn expired_entries_make_room() {
+            common::ttl_cache::expired_entries_make_room::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn expired_entries_are_hidden() {
+            common::ttl_cache::expired_entries_are_hidden::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn reused_key_is_not_expired_early() {
+            common::ttl_cache::reused_key_is_not_expired_early::<fluvio_wasm_timer::Delay>().await;
+        }
+    }
+}
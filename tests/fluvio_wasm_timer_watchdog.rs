@@ -0,0 +1,57 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_wasm32! {
+    cfg_fluvio_wasm_timer! {
+        use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        #[wasm_bindgen_test]
+        async fn missed_without_feed() {
+            common::watchdog::missed_without_feed::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn feed_postpones_the_deadline() {
+            common::watchdog::feed_postpones_the_deadline::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn feed_resets_the_count() {
+            common::watchdog::feed_resets_the_count::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn feeder() {
+            common::watchdog::feeder::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn feed_from_both_sides() {
+            common::watchdog::feed_from_both_sides::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn run() {
+            common::watchdog::run::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn stream() {
+            common::watchdog::stream::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn silent_peers() {
+            common::watchdog::silent_peers::<fluvio_wasm_timer::Delay>().await;
+        }
+
+        #[wasm_bindgen_test]
+        async fn heartbeat_tracks_peers() {
+            common::watchdog::heartbeat_tracks_peers::<fluvio_wasm_timer::Delay>().await;
+        }
+    }
+}
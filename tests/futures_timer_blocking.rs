@@ -0,0 +1,54 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_futures_timer! {
+        #[test]
+        fn sleep_until() {
+            common::blocking::sleep_until::<futures_timer::Delay>();
+        }
+
+        #[test]
+        fn sleep() {
+            common::blocking::sleep::<futures_timer::Delay>();
+        }
+
+        #[test]
+        fn tick_blocking() {
+            common::blocking::tick_blocking::<futures_timer::Delay>();
+        }
+
+        #[test]
+        fn tick_blocking_skips_missed_ticks() {
+            common::blocking::tick_blocking_skips_missed_ticks::<futures_timer::Delay>();
+        }
+
+        #[test]
+        fn next_expired_blocking() {
+            common::blocking::next_expired_blocking::<futures_timer::Delay>();
+        }
+
+        #[test]
+        fn next_expired_blocking_times_out() {
+            common::blocking::next_expired_blocking_times_out::<futures_timer::Delay>();
+        }
+
+        #[test]
+        fn timeout() {
+            common::blocking::timeout::<futures_timer::Delay>();
+        }
+
+        #[test]
+        fn timeout_elapsed() {
+            common::blocking::timeout_elapsed::<futures_timer::Delay>();
+        }
+
+        #[test]
+        #[should_panic(expected = "the closure passed to `blocking::timeout` panicked")]
+        fn timeout_panic() {
+            common::blocking::timeout_panic::<futures_timer::Delay>();
+        }
+    }
+}
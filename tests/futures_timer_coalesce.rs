@@ -0,0 +1,38 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_futures_timer! {
+        #[futures_test::test]
+        async fn overlapping_sleeps_share_a_delay() {
+            common::coalesce::overlapping_sleeps_share_a_delay::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn disjoint_sleeps_use_separate_delays() {
+            common::coalesce::disjoint_sleeps_use_separate_delays::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn dropped_sleep_leaves_its_group() {
+            common::coalesce::dropped_sleep_leaves_its_group::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn interval_joins_other_timers() {
+            common::coalesce::interval_joins_other_timers::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn delay_queue_joins_other_timers() {
+            common::coalesce::delay_queue_joins_other_timers::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn reset_within_slack_keeps_the_delay() {
+            common::coalesce::reset_within_slack_keeps_the_delay::<futures_timer::Delay>().await;
+        }
+    }
+}
@@ -0,0 +1,33 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_futures_timer! {
+        #[futures_test::test]
+        async fn send_and_receive() {
+            common::delay_channel::send_and_receive::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn cancel_message() {
+            common::delay_channel::cancel_message::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn cancel_after_receive() {
+            common::delay_channel::cancel_after_receive::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn send_from_clones() {
+            common::delay_channel::send_from_clones::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn send_after_receiver_dropped() {
+            common::delay_channel::send_after_receiver_dropped::<futures_timer::Delay>().await;
+        }
+    }
+}
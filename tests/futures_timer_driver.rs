@@ -0,0 +1,43 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_futures_timer! {
+        #[futures_test::test]
+        async fn many_sleeps_share_the_driver() {
+            common::driver::many_sleeps_share_the_driver::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn sleep_completes_at_its_deadline() {
+            common::driver::sleep_completes_at_its_deadline::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn earlier_sleep_rearms_the_delay() {
+            common::driver::earlier_sleep_rearms_the_delay::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn dropped_sleep_is_cancelled() {
+            common::driver::dropped_sleep_is_cancelled::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn reset() {
+            common::driver::reset::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn timeout() {
+            common::driver::timeout::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn driver_completes_once_dropped() {
+            common::driver::driver_completes_once_dropped::<futures_timer::Delay>().await;
+        }
+    }
+}
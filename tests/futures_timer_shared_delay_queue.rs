@@ -0,0 +1,38 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_futures_timer! {
+        #[futures_test::test]
+        async fn insert_from_task() {
+            common::shared_delay_queue::insert_from_task::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn earlier_insert_wakes_consumer() {
+            common::shared_delay_queue::earlier_insert_wakes_consumer::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn remove_and_reset() {
+            common::shared_delay_queue::remove_and_reset::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn stream_ends_when_inserters_dropped() {
+            common::shared_delay_queue::stream_ends_when_inserters_dropped::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn insert_after_consumer_dropped() {
+            common::shared_delay_queue::insert_after_consumer_dropped::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn insert_from_thread() {
+            common::shared_delay_queue::insert_from_thread::<futures_timer::Delay>().await;
+        }
+    }
+}
@@ -19,5 +19,25 @@ cfg_not_wasm32! {
         async fn reset() {
             common::sleep::reset::<futures_timer::Delay>().await;
         }
+
+        #[futures_test::test]
+        async fn is_elapsed_and_remaining() {
+            common::sleep::is_elapsed_and_remaining::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn reset_after() {
+            common::sleep::reset_after::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn never() {
+            common::sleep::never::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn max_duration() {
+            common::sleep::max_duration::<futures_timer::Delay>().await;
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,35 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_serde! {
+        cfg_futures_timer! {
+            #[futures_test::test]
+            async fn snapshot_and_restore() {
+                common::snapshot::snapshot_and_restore::<futures_timer::Delay>().await;
+            }
+
+            #[futures_test::test]
+            async fn restore_fire_immediately() {
+                common::snapshot::restore_fire_immediately::<futures_timer::Delay>().await;
+            }
+
+            #[futures_test::test]
+            async fn restore_drop() {
+                common::snapshot::restore_drop::<futures_timer::Delay>().await;
+            }
+
+            #[futures_test::test]
+            async fn restore_shift() {
+                common::snapshot::restore_shift::<futures_timer::Delay>().await;
+            }
+
+            #[futures_test::test]
+            async fn restore_keeps_fifo_order() {
+                common::snapshot::restore_keeps_fifo_order::<futures_timer::Delay>().await;
+            }
+        }
+    }
+}
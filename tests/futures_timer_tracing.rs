@@ -0,0 +1,30 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_tracing! {
+        cfg_futures_timer! {
+            #[futures_test::test]
+            async fn sleep_events() {
+                common::tracing::sleep_events::<futures_timer::Delay>().await;
+            }
+
+            #[futures_test::test]
+            async fn timeout_elapsed_with_label() {
+                common::tracing::timeout_elapsed_with_label::<futures_timer::Delay>().await;
+            }
+
+            #[futures_test::test]
+            async fn interval_missed_ticks() {
+                common::tracing::interval_missed_ticks::<futures_timer::Delay>().await;
+            }
+
+            #[futures_test::test]
+            async fn delay_queue_events() {
+                common::tracing::delay_queue_events::<futures_timer::Delay>().await;
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_futures_timer! {
+        #[futures_test::test]
+        async fn missed_without_feed() {
+            common::watchdog::missed_without_feed::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn feed_postpones_the_deadline() {
+            common::watchdog::feed_postpones_the_deadline::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn feed_resets_the_count() {
+            common::watchdog::feed_resets_the_count::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn feeder() {
+            common::watchdog::feeder::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn feed_from_both_sides() {
+            common::watchdog::feed_from_both_sides::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn run() {
+            common::watchdog::run::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn stream() {
+            common::watchdog::stream::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn silent_peers() {
+            common::watchdog::silent_peers::<futures_timer::Delay>().await;
+        }
+
+        #[futures_test::test]
+        async fn heartbeat_tracks_peers() {
+            common::watchdog::heartbeat_tracks_peers::<futures_timer::Delay>().await;
+        }
+    }
+}
@@ -18,6 +18,24 @@ macro_rules! cfg_not_wasm32 {
     }
 }
 
+macro_rules! cfg_tracing {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "tracing")]
+            $item
+        )*
+    };
+}
+
+macro_rules! cfg_std {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "std")]
+            $item
+        )*
+    };
+}
+
 macro_rules! cfg_smol {
     ($($item:item)*) => {
         $(
@@ -45,6 +63,15 @@ macro_rules! cfg_futures_timer {
     };
 }
 
+macro_rules! cfg_embassy_time {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "embassy-time")]
+            $item
+        )*
+    };
+}
+
 macro_rules! cfg_wasm_timer {
     ($($item:item)*) => {
         $(
@@ -62,3 +89,21 @@ macro_rules! cfg_fluvio_wasm_timer {
         )*
     };
 }
+
+macro_rules! cfg_quanta {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "quanta")]
+            $item
+        )*
+    };
+}
+
+macro_rules! cfg_serde {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "serde")]
+            $item
+        )*
+    };
+}
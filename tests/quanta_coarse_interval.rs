@@ -0,0 +1,53 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_quanta! {
+        cfg_smol! {
+            use std::{sync::OnceLock, time::Duration};
+
+            use timer_kit::quanta::{start_upkeep, UpkeepHandle};
+
+            type Timer = timer_kit::quanta::CoarseTimer<smol::Timer>;
+
+            /// Starts the upkeep thread once for all the tests of the process
+            fn upkeep() {
+                static UPKEEP: OnceLock<UpkeepHandle> = OnceLock::new();
+                UPKEEP.get_or_init(|| start_upkeep(Duration::from_millis(1)).unwrap());
+            }
+
+            #[smol_potat::test]
+            #[should_panic]
+            async fn interval_zero_duration() {
+                upkeep();
+                common::interval::interval_zero_duration::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn burst() {
+                upkeep();
+                common::interval::burst::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn delay() {
+                upkeep();
+                common::interval::delay::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn skip() {
+                upkeep();
+                common::interval::skip::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn reset() {
+                upkeep();
+                common::interval::reset::<Timer>().await;
+            }
+        }
+    }
+}
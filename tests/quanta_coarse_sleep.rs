@@ -0,0 +1,64 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_quanta! {
+        cfg_smol! {
+            use std::{sync::OnceLock, time::Duration};
+
+            use timer_kit::quanta::{start_upkeep, UpkeepHandle};
+
+            type Timer = timer_kit::quanta::CoarseTimer<smol::Timer>;
+
+            /// Starts the upkeep thread once for all the tests of the process
+            fn upkeep() {
+                static UPKEEP: OnceLock<UpkeepHandle> = OnceLock::new();
+                UPKEEP.get_or_init(|| start_upkeep(Duration::from_millis(1)).unwrap());
+            }
+
+            #[smol_potat::test]
+            async fn immediate_sleep() {
+                upkeep();
+                common::sleep::immediate_sleep::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn short_sleep() {
+                upkeep();
+                common::sleep::short_sleep::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn reset() {
+                upkeep();
+                common::sleep::reset::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn is_elapsed_and_remaining() {
+                upkeep();
+                common::sleep::is_elapsed_and_remaining::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn reset_after() {
+                upkeep();
+                common::sleep::reset_after::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn never() {
+                upkeep();
+                common::sleep::never::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn max_duration() {
+                upkeep();
+                common::sleep::max_duration::<Timer>().await;
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_quanta! {
+        cfg_smol! {
+            type Timer = timer_kit::quanta::Timer<smol::Timer>;
+
+            #[smol_potat::test]
+            #[should_panic]
+            async fn interval_zero_duration() {
+                common::interval::interval_zero_duration::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn burst() {
+                common::interval::burst::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn delay() {
+                common::interval::delay::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn skip() {
+                common::interval::skip::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn reset() {
+                common::interval::reset::<Timer>().await;
+            }
+        }
+    }
+}
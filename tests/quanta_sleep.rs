@@ -0,0 +1,47 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_quanta! {
+        cfg_smol! {
+            type Timer = timer_kit::quanta::Timer<smol::Timer>;
+
+            #[smol_potat::test]
+            async fn immediate_sleep() {
+                common::sleep::immediate_sleep::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn short_sleep() {
+                common::sleep::short_sleep::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn reset() {
+                common::sleep::reset::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn is_elapsed_and_remaining() {
+                common::sleep::is_elapsed_and_remaining::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn reset_after() {
+                common::sleep::reset_after::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn never() {
+                common::sleep::never::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn max_duration() {
+                common::sleep::max_duration::<Timer>().await;
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_quanta! {
+        cfg_smol! {
+            type Timer = timer_kit::quanta::Timer<smol::Timer>;
+
+            #[smol_potat::test]
+            async fn simultaneous_deadline_future_completion() {
+                common::timeout::simultaneous_deadline_future_completion::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn completed_future_past_deadline() {
+                common::timeout::completed_future_past_deadline::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn future_and_deadline_in_future() {
+                common::timeout::future_and_deadline_in_future::<Timer>().await;
+            }
+
+            #[smol_potat::test]
+            async fn deadline_future_elapses() {
+                common::timeout::deadline_future_elapses::<Timer>().await;
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_smol! {
+        #[test]
+        fn sleep_until() {
+            common::blocking::sleep_until::<smol::Timer>();
+        }
+
+        #[test]
+        fn sleep() {
+            common::blocking::sleep::<smol::Timer>();
+        }
+
+        #[test]
+        fn tick_blocking() {
+            common::blocking::tick_blocking::<smol::Timer>();
+        }
+
+        #[test]
+        fn tick_blocking_skips_missed_ticks() {
+            common::blocking::tick_blocking_skips_missed_ticks::<smol::Timer>();
+        }
+
+        #[test]
+        fn next_expired_blocking() {
+            common::blocking::next_expired_blocking::<smol::Timer>();
+        }
+
+        #[test]
+        fn next_expired_blocking_times_out() {
+            common::blocking::next_expired_blocking_times_out::<smol::Timer>();
+        }
+
+        #[test]
+        fn timeout() {
+            common::blocking::timeout::<smol::Timer>();
+        }
+
+        #[test]
+        fn timeout_elapsed() {
+            common::blocking::timeout_elapsed::<smol::Timer>();
+        }
+
+        #[test]
+        #[should_panic(expected = "the closure passed to `blocking::timeout` panicked")]
+        fn timeout_panic() {
+            common::blocking::timeout_panic::<smol::Timer>();
+        }
+    }
+}
@@ -0,0 +1,38 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_smol! {
+        #[smol_potat::test]
+        async fn overlapping_sleeps_share_a_delay() {
+            common::coalesce::overlapping_sleeps_share_a_delay::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn disjoint_sleeps_use_separate_delays() {
+            common::coalesce::disjoint_sleeps_use_separate_delays::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn dropped_sleep_leaves_its_group() {
+            common::coalesce::dropped_sleep_leaves_its_group::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn interval_joins_other_timers() {
+            common::coalesce::interval_joins_other_timers::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn delay_queue_joins_other_timers() {
+            common::coalesce::delay_queue_joins_other_timers::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn reset_within_slack_keeps_the_delay() {
+            common::coalesce::reset_within_slack_keeps_the_delay::<smol::Timer>().await;
+        }
+    }
+}
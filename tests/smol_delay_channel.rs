@@ -0,0 +1,33 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_smol! {
+        #[smol_potat::test]
+        async fn send_and_receive() {
+            common::delay_channel::send_and_receive::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn cancel_message() {
+            common::delay_channel::cancel_message::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn cancel_after_receive() {
+            common::delay_channel::cancel_after_receive::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn send_from_clones() {
+            common::delay_channel::send_from_clones::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn send_after_receiver_dropped() {
+            common::delay_channel::send_after_receiver_dropped::<smol::Timer>().await;
+        }
+    }
+}
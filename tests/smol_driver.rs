@@ -0,0 +1,43 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_smol! {
+        #[smol_potat::test]
+        async fn many_sleeps_share_the_driver() {
+            common::driver::many_sleeps_share_the_driver::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn sleep_completes_at_its_deadline() {
+            common::driver::sleep_completes_at_its_deadline::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn earlier_sleep_rearms_the_delay() {
+            common::driver::earlier_sleep_rearms_the_delay::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn dropped_sleep_is_cancelled() {
+            common::driver::dropped_sleep_is_cancelled::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn reset() {
+            common::driver::reset::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn timeout() {
+            common::driver::timeout::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn driver_completes_once_dropped() {
+            common::driver::driver_completes_once_dropped::<smol::Timer>().await;
+        }
+    }
+}
@@ -19,5 +19,25 @@ cfg_not_wasm32! {
         async fn reset() {
             common::sleep::reset::<smol::Timer>().await;
         }
+    
+        #[smol_potat::test]
+        async fn is_elapsed_and_remaining() {
+            common::sleep::is_elapsed_and_remaining::<smol::Timer>().await;
+        }
+    
+        #[smol_potat::test]
+        async fn reset_after() {
+            common::sleep::reset_after::<smol::Timer>().await;
+        }
+    
+        #[smol_potat::test]
+        async fn never() {
+            common::sleep::never::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn max_duration() {
+            common::sleep::max_duration::<smol::Timer>().await;
+        }
     }
 }
\ No newline at end of file
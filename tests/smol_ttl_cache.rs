@@ -0,0 +1,38 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_smol! {
+        #[smol_potat::test]
+        async fn insert_get_and_expire() {
+            common::ttl_cache::insert_get_and_expire::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn sliding_expiration() {
+            common::ttl_cache::sliding_expiration::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn lru_eviction() {
+            common::ttl_cache::lru_eviction::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn expired_entries_make_room() {
+            common::ttl_cache::expired_entries_make_room::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn expired_entries_are_hidden() {
+            common::ttl_cache::expired_entries_are_hidden::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn reused_key_is_not_expired_early() {
+            common::ttl_cache::reused_key_is_not_expired_early::<smol::Timer>().await;
+        }
+    }
+}
@@ -0,0 +1,53 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_smol! {
+        #[smol_potat::test]
+        async fn missed_without_feed() {
+            common::watchdog::missed_without_feed::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn feed_postpones_the_deadline() {
+            common::watchdog::feed_postpones_the_deadline::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn feed_resets_the_count() {
+            common::watchdog::feed_resets_the_count::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn feeder() {
+            common::watchdog::feeder::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn feed_from_both_sides() {
+            common::watchdog::feed_from_both_sides::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn run() {
+            common::watchdog::run::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn stream() {
+            common::watchdog::stream::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn silent_peers() {
+            common::watchdog::silent_peers::<smol::Timer>().await;
+        }
+
+        #[smol_potat::test]
+        async fn heartbeat_tracks_peers() {
+            common::watchdog::heartbeat_tracks_peers::<smol::Timer>().await;
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use timer_kit::TimerWheel;
+
+/// Number of ticks covered by the levels of the wheel
+const WHEEL_RANGE: u64 = 1 << 36;
+
+#[test]
+fn advance_yields_due_values_in_order() {
+    let mut wheel = TimerWheel::new();
+    for when in [300, 5, 64, 4096, 65, 5] {
+        wheel.insert(when, when);
+    }
+    assert_eq!(wheel.len(), 6);
+    assert_eq!(wheel.next_expiration(), Some(5));
+
+    let expired: Vec<_> = wheel.advance(100).map(|(when, _)| when).collect();
+    assert_eq!(expired, vec![5, 5, 64, 65]);
+    assert_eq!(wheel.elapsed(), 100);
+    assert_eq!(wheel.next_expiration(), Some(300));
+
+    assert_eq!(wheel.advance(299).count(), 0);
+    let expired: Vec<_> = wheel.advance(10_000).collect();
+    assert_eq!(expired, vec![(300, 300), (4096, 4096)]);
+    assert!(wheel.is_empty());
+    assert_eq!(wheel.next_expiration(), None);
+}
+
+#[test]
+fn next_expiration_is_exact() {
+    let mut wheel = TimerWheel::new();
+    // Both values share a slot of the second level
+    wheel.insert(100, "foo");
+    wheel.insert(90, "bar");
+    assert_eq!(wheel.next_expiration(), Some(90));
+
+    assert_eq!(wheel.advance(89).count(), 0);
+    assert_eq!(wheel.next_expiration(), Some(90));
+    assert_eq!(wheel.advance(90).collect::<Vec<_>>(), vec![(90, "bar")]);
+    assert_eq!(wheel.next_expiration(), Some(100));
+}
+
+#[test]
+fn cancel_and_stale_handles() {
+    let mut wheel = TimerWheel::new();
+    let first = wheel.insert(10, "foo");
+    let second = wheel.insert(10, "bar");
+    assert_eq!(wheel.get(first), Some(&"foo"));
+    assert_eq!(wheel.deadline(second), Some(10));
+
+    assert_eq!(wheel.cancel(first), Some("foo"));
+    assert_eq!(wheel.cancel(first), None);
+    assert!(!wheel.contains(first));
+
+    // The slot of the cancelled value is reused by a new value
+    let third = wheel.insert(20, "baz");
+    assert_ne!(first, third);
+    assert_eq!(wheel.get(first), None);
+    *wheel.get_mut(third).unwrap() = "qux";
+
+    assert_eq!(wheel.advance(15).collect::<Vec<_>>(), vec![(10, "bar")]);
+    assert_eq!(wheel.cancel(second), None);
+    assert_eq!(wheel.cancel(third), Some("qux"));
+    assert!(wheel.is_empty());
+}
+
+#[test]
+fn insert_already_due() {
+    let mut wheel = TimerWheel::new();
+    wheel.advance(50).for_each(drop);
+
+    wheel.insert(60, "later");
+    wheel.insert(50, "now");
+    let handle = wheel.insert(10, "past");
+    wheel.insert(20, "also past");
+    assert_eq!(wheel.next_expiration(), Some(10));
+    assert_eq!(wheel.cancel(handle), Some("past"));
+    assert_eq!(wheel.next_expiration(), Some(20));
+
+    let expired: Vec<_> = wheel.advance(60).collect();
+    assert_eq!(expired, vec![(50, "now"), (20, "also past"), (60, "later")]);
+}
+
+#[test]
+fn dropped_advance_resumes() {
+    let mut wheel = TimerWheel::new();
+    for when in 1..=10 {
+        wheel.insert(when, when);
+    }
+
+    let expired: Vec<_> = wheel.advance(10).take(2).collect();
+    assert_eq!(expired, vec![(1, 1), (2, 2)]);
+    assert_eq!(wheel.len(), 8);
+
+    // The values that were due stay due even if the wheel is advanced to an
+    // earlier tick
+    assert_eq!(wheel.elapsed(), 10);
+    let expired: Vec<_> = wheel.advance(5).map(|(_, value)| value).collect();
+    assert_eq!(expired, (3..=10).collect::<Vec<_>>());
+}
+
+#[test]
+fn far_deadlines() {
+    let mut wheel = TimerWheel::new();
+    let far = 3 * WHEEL_RANGE + 7;
+    let handle = wheel.insert(far + 1, "cancelled");
+    wheel.insert(far, "far");
+    wheel.insert(10, "near");
+    assert_eq!(wheel.cancel(handle), Some("cancelled"));
+
+    assert_eq!(wheel.advance(far - 1).collect::<Vec<_>>(), vec![(10, "near")]);
+    assert_eq!(wheel.next_expiration(), Some(far));
+    assert_eq!(wheel.advance(far).collect::<Vec<_>>(), vec![(far, "far")]);
+
+    // A single advance over the whole range still yields far values
+    wheel.insert(far + 2 * WHEEL_RANGE, "farther");
+    assert_eq!(wheel.advance(u64::MAX).count(), 1);
+}
+
+#[test]
+fn clear_keeps_elapsed() {
+    let mut wheel = TimerWheel::new();
+    wheel.insert(5, ());
+    wheel.advance(1000).for_each(drop);
+    wheel.insert(2000, ());
+    wheel.insert(WHEEL_RANGE * 2, ());
+
+    wheel.clear();
+    assert!(wheel.is_empty());
+    assert_eq!(wheel.elapsed(), 1000);
+    assert_eq!(wheel.next_expiration(), None);
+
+    wheel.insert(1500, ());
+    assert_eq!(wheel.advance(1499).count(), 0);
+    assert_eq!(wheel.advance(1500).count(), 1);
+}
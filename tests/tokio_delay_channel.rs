@@ -0,0 +1,33 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_tokio! {
+        #[tokio::test]
+        async fn send_and_receive() {
+            common::delay_channel::send_and_receive::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn cancel_message() {
+            common::delay_channel::cancel_message::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn cancel_after_receive() {
+            common::delay_channel::cancel_after_receive::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn send_from_clones() {
+            common::delay_channel::send_from_clones::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn send_after_receiver_dropped() {
+            common::delay_channel::send_after_receiver_dropped::<tokio::time::Sleep>().await;
+        }
+    }
+}
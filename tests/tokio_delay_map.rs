@@ -0,0 +1,28 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_tokio! {
+        #[tokio::test]
+        async fn insert_and_expire() {
+            common::delay_map::insert_and_expire::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn insert_replaces_existing() {
+            common::delay_map::insert_replaces_existing::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn remove_and_reset() {
+            common::delay_map::remove_and_reset::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn reinsert_after_expiry() {
+            common::delay_map::reinsert_after_expiry::<tokio::time::Sleep>().await;
+        }
+    }
+}
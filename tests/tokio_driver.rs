@@ -0,0 +1,43 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_tokio! {
+        #[tokio::test]
+        async fn many_sleeps_share_the_driver() {
+            common::driver::many_sleeps_share_the_driver::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn sleep_completes_at_its_deadline() {
+            common::driver::sleep_completes_at_its_deadline::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn earlier_sleep_rearms_the_delay() {
+            common::driver::earlier_sleep_rearms_the_delay::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn dropped_sleep_is_cancelled() {
+            common::driver::dropped_sleep_is_cancelled::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn reset() {
+            common::driver::reset::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn timeout() {
+            common::driver::timeout::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn driver_completes_once_dropped() {
+            common::driver::driver_completes_once_dropped::<tokio::time::Sleep>().await;
+        }
+    }
+}
@@ -19,5 +19,25 @@ cfg_not_wasm32! {
         async fn reset() {
             common::sleep::reset::<tokio::time::Sleep>().await;
         }
+    
+        #[tokio::test]
+        async fn is_elapsed_and_remaining() {
+            common::sleep::is_elapsed_and_remaining::<tokio::time::Sleep>().await;
+        }
+    
+        #[tokio::test]
+        async fn reset_after() {
+            common::sleep::reset_after::<tokio::time::Sleep>().await;
+        }
+    
+        #[tokio::test]
+        async fn never() {
+            common::sleep::never::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn max_duration() {
+            common::sleep::max_duration::<tokio::time::Sleep>().await;
+        }
     }
 }
@@ -0,0 +1,35 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_serde! {
+        cfg_tokio! {
+            #[tokio::test]
+            async fn snapshot_and_restore() {
+                common::snapshot::snapshot_and_restore::<tokio::time::Sleep>().await;
+            }
+
+            #[tokio::test]
+            async fn restore_fire_immediately() {
+                common::snapshot::restore_fire_immediately::<tokio::time::Sleep>().await;
+            }
+
+            #[tokio::test]
+            async fn restore_drop() {
+                common::snapshot::restore_drop::<tokio::time::Sleep>().await;
+            }
+
+            #[tokio::test]
+            async fn restore_shift() {
+                common::snapshot::restore_shift::<tokio::time::Sleep>().await;
+            }
+
+            #[tokio::test]
+            async fn restore_keeps_fifo_order() {
+                common::snapshot::restore_keeps_fifo_order::<tokio::time::Sleep>().await;
+            }
+        }
+    }
+}
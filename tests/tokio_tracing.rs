@@ -0,0 +1,30 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_tracing! {
+        cfg_tokio! {
+            #[tokio::test]
+            async fn sleep_events() {
+                common::tracing::sleep_events::<tokio::time::Sleep>().await;
+            }
+
+            #[tokio::test]
+            async fn timeout_elapsed_with_label() {
+                common::tracing::timeout_elapsed_with_label::<tokio::time::Sleep>().await;
+            }
+
+            #[tokio::test]
+            async fn interval_missed_ticks() {
+                common::tracing::interval_missed_ticks::<tokio::time::Sleep>().await;
+            }
+
+            #[tokio::test]
+            async fn delay_queue_events() {
+                common::tracing::delay_queue_events::<tokio::time::Sleep>().await;
+            }
+        }
+    }
+}
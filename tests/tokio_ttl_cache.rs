@@ -0,0 +1,38 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_tokio! {
+        #[tokio::test]
+        async fn insert_get_and_expire() {
+            common::ttl_cache::insert_get_and_expire::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn sliding_expiration() {
+            common::ttl_cache::sliding_expiration::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn lru_eviction() {
+            common::ttl_cache::lru_eviction::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn expired_entries_make_room() {
+            common::ttl_cache::expired_entries_make_room::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn expired_entries_are_hidden() {
+            common::ttl_cache::expired_entries_are_hidden::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn reused_key_is_not_expired_early() {
+            common::ttl_cache::reused_key_is_not_expired_early::<tokio::time::Sleep>().await;
+        }
+    }
+}
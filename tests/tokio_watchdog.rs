@@ -0,0 +1,53 @@
+#[macro_use]
+mod macros;
+
+mod common;
+
+cfg_not_wasm32! {
+    cfg_tokio! {
+        #[tokio::test]
+        async fn missed_without_feed() {
+            common::watchdog::missed_without_feed::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn feed_postpones_the_deadline() {
+            common::watchdog::feed_postpones_the_deadline::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn feed_resets_the_count() {
+            common::watchdog::feed_resets_the_count::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn feeder() {
+            common::watchdog::feeder::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn feed_from_both_sides() {
+            common::watchdog::feed_from_both_sides::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn run() {
+            common::watchdog::run::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn stream() {
+            common::watchdog::stream::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn silent_peers() {
+            common::watchdog::silent_peers::<tokio::time::Sleep>().await;
+        }
+
+        #[tokio::test]
+        async fn heartbeat_tracks_peers() {
+            common::watchdog::heartbeat_tracks_peers::<tokio::time::Sleep>().await;
+        }
+    }
+}